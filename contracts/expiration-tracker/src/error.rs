@@ -0,0 +1,31 @@
+use loam_sdk::soroban_sdk::{self, contracterror};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// The tracker has not been initialized with `tracker_init`
+    NotInitialized = 1,
+    /// The tracker has already been initialized
+    AlreadyInitialized = 2,
+    /// The contract's Core admin must be set before initializing the tracker
+    NoCoreAdmin = 3,
+    /// The reward cannot be negative, the threshold must be lower than the extension, and the
+    /// extension cannot be longer than the network's maximum TTL
+    InvalidConfig = 4,
+    /// The entry is already tracked
+    AlreadyTracked = 5,
+    /// The entry is not tracked
+    NotTracked = 6,
+    /// Only the owner of a tracked entry can stop tracking it
+    NotOwner = 7,
+    /// The entry is not within the threshold of its expiration yet
+    NotDue = 8,
+    /// The owner's deposit does not cover the requested amount or the reward
+    InsufficientDeposit = 9,
+    /// Amounts must be positive
+    InvalidAmount = 10,
+    /// Withdrawing would leave less than the minimum deposit for each of the owner's tracked
+    /// entries; untrack entries first
+    DepositInUse = 11,
+}
@@ -0,0 +1,16 @@
+#![no_std]
+use loam_sdk::derive_contract;
+use loam_subcontract_core::{admin::Admin, Core};
+
+pub mod error;
+mod storage;
+pub mod subcontract;
+
+pub use error::Error;
+use subcontract::{ExpirationTracker, Tracker};
+pub use subcontract::{Target, Tracked, TrackerConfig, MAX_PAGE};
+
+#[derive_contract(Core(Admin), Tracker(ExpirationTracker))]
+pub struct Contract;
+
+mod test;
@@ -0,0 +1,131 @@
+pub(crate) use loam_sdk::soroban_sdk::ttl::extend_instance;
+use loam_sdk::soroban_sdk::ttl::DAY_IN_LEDGERS;
+use loam_sdk::soroban_sdk::{self, contracttype, env, Address};
+
+use crate::{Target, Tracked, TrackerConfig};
+
+pub(crate) const ENTRY_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+pub(crate) const ENTRY_LIFETIME_THRESHOLD: u32 = ENTRY_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub(crate) enum StorageKey {
+    Config,
+    TrackedCount,
+    TargetAt(u32),
+    Tracked(Target),
+    Deposit(Address),
+    OwnedCount(Address),
+}
+
+pub(crate) fn read_config() -> Option<TrackerConfig> {
+    env().storage().instance().get(&StorageKey::Config)
+}
+
+pub(crate) fn write_config(config: &TrackerConfig) {
+    env().storage().instance().set(&StorageKey::Config, config);
+}
+
+/// Number of tracked entries. They are listed at indexes `0..count`, so upcoming expirations can
+/// be paged through
+pub(crate) fn read_tracked_count() -> u32 {
+    env()
+        .storage()
+        .instance()
+        .get(&StorageKey::TrackedCount)
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_tracked_count(count: u32) {
+    env()
+        .storage()
+        .instance()
+        .set(&StorageKey::TrackedCount, &count);
+}
+
+pub(crate) fn read_target_at(index: u32) -> Option<Target> {
+    let key = StorageKey::TargetAt(index);
+    let storage = env().storage().persistent();
+    let target = storage.get(&key)?;
+    storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+    Some(target)
+}
+
+pub(crate) fn write_target_at(index: u32, target: &Target) {
+    let key = StorageKey::TargetAt(index);
+    let storage = env().storage().persistent();
+    storage.set(&key, target);
+    storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+}
+
+pub(crate) fn remove_target_at(index: u32) {
+    env()
+        .storage()
+        .persistent()
+        .remove(&StorageKey::TargetAt(index));
+}
+
+pub(crate) fn read_tracked(target: &Target) -> Option<Tracked> {
+    let key = StorageKey::Tracked(target.clone());
+    let storage = env().storage().persistent();
+    let tracked = storage.get(&key)?;
+    storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+    Some(tracked)
+}
+
+pub(crate) fn write_tracked(target: &Target, tracked: &Tracked) {
+    let key = StorageKey::Tracked(target.clone());
+    let storage = env().storage().persistent();
+    storage.set(&key, tracked);
+    storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+}
+
+pub(crate) fn remove_tracked(target: &Target) {
+    env()
+        .storage()
+        .persistent()
+        .remove(&StorageKey::Tracked(target.clone()));
+}
+
+pub(crate) fn read_deposit(owner: &Address) -> i128 {
+    let key = StorageKey::Deposit(owner.clone());
+    let storage = env().storage().persistent();
+    match storage.get(&key) {
+        Some(deposit) => {
+            storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+            deposit
+        }
+        None => 0,
+    }
+}
+
+pub(crate) fn write_deposit(owner: &Address, deposit: i128) {
+    let key = StorageKey::Deposit(owner.clone());
+    let storage = env().storage().persistent();
+    storage.set(&key, &deposit);
+    storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+}
+
+/// Number of entries tracked with `owner`'s deposit, each of which needs the minimum deposit
+pub(crate) fn read_owned_count(owner: &Address) -> u32 {
+    let key = StorageKey::OwnedCount(owner.clone());
+    let storage = env().storage().persistent();
+    match storage.get(&key) {
+        Some(count) => {
+            storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+            count
+        }
+        None => 0,
+    }
+}
+
+pub(crate) fn write_owned_count(owner: &Address, count: u32) {
+    let key = StorageKey::OwnedCount(owner.clone());
+    let storage = env().storage().persistent();
+    if count == 0 {
+        storage.remove(&key);
+    } else {
+        storage.set(&key, &count);
+        storage.extend_ttl(&key, ENTRY_LIFETIME_THRESHOLD, ENTRY_BUMP_AMOUNT);
+    }
+}
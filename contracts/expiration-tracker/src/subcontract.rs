@@ -0,0 +1,328 @@
+use loam_sdk::{
+    soroban_sdk::{self, contracttype, env, token::TokenClient, Address, Lazy, Vec},
+    subcontract,
+};
+use loam_subcontract_core::admin::{Admin, IsCore};
+
+use crate::{storage, Error};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TrackerConfig {
+    /// Token that deposits and rewards are paid in
+    pub token: Address,
+    /// Paid out of the owner's deposit to whoever extends an entry
+    pub reward: i128,
+    /// Entries can be extended once they are expected to expire within this many ledgers
+    pub threshold: u32,
+    /// Number of ledgers entries are extended to
+    pub extend_to: u32,
+    /// Deposit an owner must hold for each entry they track, so entries cannot be tracked for free
+    pub min_deposit: i128,
+}
+
+/// Most entries `upcoming` looks through in one call
+pub const MAX_PAGE: u32 = 100;
+
+/// An entry whose TTL can be extended by the tracker
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Target {
+    /// The instance of a contract
+    Contract(Address),
+    /// The Wasm run by a contract. Contracts can only extend Wasm through a contract using it
+    Wasm(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tracked {
+    pub target: Target,
+    /// Address whose deposit pays for extending the entry
+    pub owner: Address,
+    /// Ledger the entry is expected to live until. Contracts cannot read TTLs, so this is the
+    /// ledger the tracker last extended it to, and the entry may live longer
+    pub expires: u32,
+    /// Position of the entry among all tracked entries, for paging through `upcoming`
+    pub index: u32,
+}
+
+#[derive(Lazy, Default)]
+pub struct ExpirationTracker;
+
+#[subcontract]
+pub trait IsTracker {
+    /// Set the token, reward, threshold, extension and minimum deposit. Requires the auth of the
+    /// Core admin
+    #[allow(clippy::missing_errors_doc)]
+    fn tracker_init(&mut self, config: crate::TrackerConfig) -> Result<(), crate::Error>;
+
+    /// The token, reward, threshold, extension and minimum deposit
+    #[allow(clippy::missing_errors_doc)]
+    fn tracker_config(&self) -> Result<crate::TrackerConfig, crate::Error>;
+
+    /// Deposit tokens to pay for extending the owner's entries
+    #[allow(clippy::missing_errors_doc)]
+    fn deposit(
+        &mut self,
+        owner: loam_sdk::soroban_sdk::Address,
+        amount: i128,
+    ) -> Result<(), crate::Error>;
+
+    /// Withdraw tokens that have not been paid out, keeping the minimum deposit for each entry
+    /// the owner tracks
+    #[allow(clippy::missing_errors_doc)]
+    fn withdraw(
+        &mut self,
+        owner: loam_sdk::soroban_sdk::Address,
+        amount: i128,
+    ) -> Result<(), crate::Error>;
+
+    /// The amount an owner has left to pay for extensions
+    fn deposit_of(&self, owner: loam_sdk::soroban_sdk::Address) -> i128;
+
+    /// Start tracking an entry, extending it right away. The owner's deposit must be at least the
+    /// configured minimum for each entry they track, including this one
+    #[allow(clippy::missing_errors_doc)]
+    fn track(
+        &mut self,
+        owner: loam_sdk::soroban_sdk::Address,
+        target: crate::Target,
+    ) -> Result<(), crate::Error>;
+
+    /// Stop tracking an entry
+    #[allow(clippy::missing_errors_doc)]
+    fn untrack(
+        &mut self,
+        owner: loam_sdk::soroban_sdk::Address,
+        target: crate::Target,
+    ) -> Result<(), crate::Error>;
+
+    /// Extend an entry that is within the threshold of its expiration. Anyone can call this, and
+    /// `caller` is paid the reward out of the owner's deposit
+    #[allow(clippy::missing_errors_doc)]
+    fn extend(
+        &mut self,
+        caller: loam_sdk::soroban_sdk::Address,
+        target: crate::Target,
+    ) -> Result<(), crate::Error>;
+
+    /// Look up a tracked entry
+    #[allow(clippy::missing_errors_doc)]
+    fn tracked(&self, target: crate::Target) -> Result<crate::Tracked, crate::Error>;
+
+    /// The number of tracked entries, which `upcoming` pages through
+    fn tracked_count(&self) -> u32;
+
+    /// Of the `limit` tracked entries from index `start`, those expected to expire within the
+    /// given number of ledgers, soonest first. At most `MAX_PAGE` entries are looked through
+    fn upcoming(
+        &self,
+        within: u32,
+        start: u32,
+        limit: u32,
+    ) -> loam_sdk::soroban_sdk::Vec<crate::Tracked>;
+}
+
+fn config() -> Result<TrackerConfig, Error> {
+    storage::read_config().ok_or(Error::NotInitialized)
+}
+
+/// Extends an entry and returns the ledger it now lives until, at least
+fn extend_target(target: &Target, extend_to: u32) -> Result<u32, Error> {
+    // The network's maximum TTL can be lowered after the tracker is initialized
+    if extend_to > env().storage().max_ttl() {
+        return Err(Error::InvalidConfig);
+    }
+    let deployer = env().deployer();
+    match target {
+        Target::Contract(id) => {
+            deployer.extend_ttl_for_contract_instance(id.clone(), extend_to, extend_to);
+        }
+        Target::Wasm(id) => deployer.extend_ttl_for_code(id.clone(), extend_to, extend_to),
+    }
+    Ok(env().ledger().sequence() + extend_to)
+}
+
+/// The deposit `owner` must keep to track `count` entries
+fn required_deposit(config: &TrackerConfig, count: u32) -> i128 {
+    config.min_deposit.saturating_mul(i128::from(count))
+}
+
+impl IsTracker for ExpirationTracker {
+    fn tracker_init(&mut self, config: TrackerConfig) -> Result<(), Error> {
+        let admin = Admin::get_lazy()
+            .and_then(|admin| admin.admin_get())
+            .ok_or(Error::NoCoreAdmin)?;
+        admin.require_auth();
+        if storage::read_config().is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+        if config.reward < 0
+            || config.min_deposit < 0
+            || config.threshold >= config.extend_to
+            || config.extend_to > env().storage().max_ttl()
+        {
+            return Err(Error::InvalidConfig);
+        }
+        storage::write_config(&config);
+        Ok(())
+    }
+
+    fn tracker_config(&self) -> Result<TrackerConfig, Error> {
+        config()
+    }
+
+    fn deposit(&mut self, owner: Address, amount: i128) -> Result<(), Error> {
+        owner.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let config = config()?;
+        TokenClient::new(env(), &config.token).transfer(
+            &owner,
+            &env().current_contract_address(),
+            &amount,
+        );
+        storage::extend_instance();
+        storage::write_deposit(&owner, storage::read_deposit(&owner) + amount);
+        Ok(())
+    }
+
+    fn withdraw(&mut self, owner: Address, amount: i128) -> Result<(), Error> {
+        owner.require_auth();
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let deposit = storage::read_deposit(&owner);
+        if deposit < amount {
+            return Err(Error::InsufficientDeposit);
+        }
+        let config = config()?;
+        if deposit - amount < required_deposit(&config, storage::read_owned_count(&owner)) {
+            return Err(Error::DepositInUse);
+        }
+        storage::extend_instance();
+        storage::write_deposit(&owner, deposit - amount);
+        TokenClient::new(env(), &config.token).transfer(
+            &env().current_contract_address(),
+            &owner,
+            &amount,
+        );
+        Ok(())
+    }
+
+    fn deposit_of(&self, owner: Address) -> i128 {
+        storage::read_deposit(&owner)
+    }
+
+    fn track(&mut self, owner: Address, target: Target) -> Result<(), Error> {
+        owner.require_auth();
+        let config = config()?;
+        if storage::read_tracked(&target).is_some() {
+            return Err(Error::AlreadyTracked);
+        }
+        let owned_count = storage::read_owned_count(&owner);
+        if storage::read_deposit(&owner) < required_deposit(&config, owned_count + 1) {
+            return Err(Error::InsufficientDeposit);
+        }
+        let expires = extend_target(&target, config.extend_to)?;
+        storage::extend_instance();
+        storage::write_owned_count(&owner, owned_count + 1);
+        let index = storage::read_tracked_count();
+        storage::write_tracked_count(index + 1);
+        storage::write_target_at(index, &target);
+        storage::write_tracked(
+            &target,
+            &Tracked {
+                target: target.clone(),
+                owner,
+                expires,
+                index,
+            },
+        );
+        Ok(())
+    }
+
+    fn untrack(&mut self, owner: Address, target: Target) -> Result<(), Error> {
+        owner.require_auth();
+        let tracked = storage::read_tracked(&target).ok_or(Error::NotTracked)?;
+        if tracked.owner != owner {
+            return Err(Error::NotOwner);
+        }
+        storage::extend_instance();
+        // Move the last entry into the removed one's place, so indexes stay contiguous
+        let last = storage::read_tracked_count() - 1;
+        if tracked.index != last {
+            if let Some(moved) = storage::read_target_at(last) {
+                let mut moved_tracked = storage::read_tracked(&moved).ok_or(Error::NotTracked)?;
+                moved_tracked.index = tracked.index;
+                storage::write_target_at(tracked.index, &moved);
+                storage::write_tracked(&moved, &moved_tracked);
+            }
+        }
+        storage::remove_target_at(last);
+        storage::write_tracked_count(last);
+        storage::remove_tracked(&target);
+        storage::write_owned_count(&owner, storage::read_owned_count(&owner) - 1);
+        Ok(())
+    }
+
+    fn extend(&mut self, caller: Address, target: Target) -> Result<(), Error> {
+        let config = config()?;
+        let mut tracked = storage::read_tracked(&target).ok_or(Error::NotTracked)?;
+        if tracked.expires.saturating_sub(env().ledger().sequence()) > config.threshold {
+            return Err(Error::NotDue);
+        }
+        let deposit = storage::read_deposit(&tracked.owner);
+        if deposit < config.reward {
+            return Err(Error::InsufficientDeposit);
+        }
+        tracked.expires = extend_target(&target, config.extend_to)?;
+        storage::extend_instance();
+        storage::write_deposit(&tracked.owner, deposit - config.reward);
+        storage::write_tracked(&target, &tracked);
+        if config.reward > 0 {
+            TokenClient::new(env(), &config.token).transfer(
+                &env().current_contract_address(),
+                &caller,
+                &config.reward,
+            );
+        }
+        Ok(())
+    }
+
+    fn tracked(&self, target: Target) -> Result<Tracked, Error> {
+        storage::read_tracked(&target).ok_or(Error::NotTracked)
+    }
+
+    fn tracked_count(&self) -> u32 {
+        storage::read_tracked_count()
+    }
+
+    fn upcoming(&self, within: u32, start: u32, limit: u32) -> Vec<Tracked> {
+        let until = env().ledger().sequence().saturating_add(within);
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE))
+            .min(storage::read_tracked_count());
+        let mut upcoming: Vec<Tracked> = Vec::new(env());
+        for index in start..end {
+            let Some(tracked) =
+                storage::read_target_at(index).and_then(|target| storage::read_tracked(&target))
+            else {
+                continue;
+            };
+            if tracked.expires > until {
+                continue;
+            }
+            // Insertion sort, keeping the soonest expirations first
+            let mut index = 0;
+            while index < upcoming.len() && upcoming.get_unchecked(index).expires <= tracked.expires
+            {
+                index += 1;
+            }
+            upcoming.insert(index, tracked);
+        }
+        upcoming
+    }
+}
@@ -0,0 +1,360 @@
+#![cfg(test)]
+use loam_sdk::soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env, Vec,
+};
+
+use crate::{Error, SorobanContract__, SorobanContract__Client, Target, Tracked, TrackerConfig};
+
+struct Setup {
+    client: SorobanContract__Client<'static>,
+    token: TokenClient<'static>,
+    /// Holds 1000 tokens, 500 of them deposited
+    owner: Address,
+    keeper: Address,
+}
+
+/// A tracker paying a reward of 10 for extending entries to 1000 ledgers once they expire within
+/// 100, and requiring a deposit of 100 to track an entry
+fn tracker(env: &Env) -> Setup {
+    env.mock_all_auths();
+    let client = SorobanContract__Client::new(env, &env.register_contract(None, SorobanContract__));
+    let admin = Address::generate(env);
+    let owner = Address::generate(env);
+    let token = env.register_stellar_asset_contract(admin.clone());
+    StellarAssetClient::new(env, &token).mint(&owner, &1000);
+
+    client.admin_set(&admin);
+    client.tracker_init(&TrackerConfig {
+        token: token.clone(),
+        reward: 10,
+        threshold: 100,
+        extend_to: 1000,
+        min_deposit: 100,
+    });
+    client.deposit(&owner, &500);
+    Setup {
+        client,
+        token: TokenClient::new(env, &token),
+        owner,
+        keeper: Address::generate(env),
+    }
+}
+
+fn pass_ledgers(env: &Env, ledgers: u32) {
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number += ledgers);
+}
+
+fn targets(upcoming: &Vec<Tracked>) -> Vec<Target> {
+    let mut targets = Vec::new(upcoming.env());
+    for tracked in upcoming.iter() {
+        targets.push_back(tracked.target);
+    }
+    targets
+}
+
+#[test]
+fn deposit_and_withdraw() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        owner,
+        ..
+    } = tracker(&env);
+    assert_eq!(client.deposit_of(&owner), 500);
+    assert_eq!(token.balance(&owner), 500);
+
+    client.withdraw(&owner, &200);
+    assert_eq!(client.deposit_of(&owner), 300);
+    assert_eq!(token.balance(&owner), 700);
+    assert_eq!(
+        client.try_withdraw(&owner, &301),
+        Err(Ok(Error::InsufficientDeposit))
+    );
+    assert_eq!(
+        client.try_deposit(&owner, &0),
+        Err(Ok(Error::InvalidAmount))
+    );
+}
+
+#[test]
+fn withdraw_keeps_min_deposit() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        owner,
+        ..
+    } = tracker(&env);
+    let first = Target::Contract(client.address.clone());
+    let second = Target::Contract(token.address.clone());
+    client.track(&owner, &first);
+    client.track(&owner, &second);
+
+    assert_eq!(
+        client.try_withdraw(&owner, &301),
+        Err(Ok(Error::DepositInUse))
+    );
+    client.withdraw(&owner, &300);
+    assert_eq!(
+        client.try_track(&owner, &Target::Wasm(client.address.clone())),
+        Err(Ok(Error::InsufficientDeposit))
+    );
+
+    client.untrack(&owner, &first);
+    client.withdraw(&owner, &100);
+    assert_eq!(client.deposit_of(&owner), 100);
+    client.untrack(&owner, &second);
+    client.withdraw(&owner, &100);
+    assert_eq!(token.balance(&owner), 1000);
+}
+
+#[test]
+fn track_and_extend() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        owner,
+        keeper,
+    } = tracker(&env);
+    let target = Target::Contract(client.address.clone());
+
+    client.track(&owner, &target);
+    assert_eq!(
+        client.tracked(&target).expires,
+        env.ledger().sequence() + 1000
+    );
+    assert_eq!(client.try_extend(&keeper, &target), Err(Ok(Error::NotDue)));
+
+    pass_ledgers(&env, 950);
+    client.extend(&keeper, &target);
+    assert_eq!(
+        client.tracked(&target).expires,
+        env.ledger().sequence() + 1000
+    );
+    assert_eq!(client.deposit_of(&owner), 490);
+    assert_eq!(token.balance(&keeper), 10);
+}
+
+#[test]
+fn track_twice() {
+    let env = Env::default();
+    let Setup { client, owner, .. } = tracker(&env);
+    let target = Target::Contract(client.address.clone());
+    client.track(&owner, &target);
+    assert_eq!(
+        client.try_track(&owner, &target),
+        Err(Ok(Error::AlreadyTracked))
+    );
+}
+
+#[test]
+fn track_without_min_deposit() {
+    let env = Env::default();
+    let Setup { client, .. } = tracker(&env);
+    assert_eq!(
+        client.try_track(
+            &Address::generate(&env),
+            &Target::Contract(client.address.clone())
+        ),
+        Err(Ok(Error::InsufficientDeposit))
+    );
+}
+
+#[test]
+fn extend_without_deposit() {
+    let env = Env::default();
+    let Setup {
+        client,
+        owner,
+        keeper,
+        ..
+    } = tracker(&env);
+    let target = Target::Contract(client.address.clone());
+    client.track(&owner, &target);
+    client.withdraw(&owner, &400);
+
+    // The minimum deposit left pays for ten extensions
+    for _ in 0..10 {
+        pass_ledgers(&env, 950);
+        client.extend(&keeper, &target);
+    }
+    assert_eq!(client.deposit_of(&owner), 0);
+    pass_ledgers(&env, 950);
+    assert_eq!(
+        client.try_extend(&keeper, &target),
+        Err(Ok(Error::InsufficientDeposit))
+    );
+}
+
+#[test]
+fn untrack() {
+    let env = Env::default();
+    let Setup { client, owner, .. } = tracker(&env);
+    let target = Target::Contract(client.address.clone());
+    client.track(&owner, &target);
+
+    assert_eq!(
+        client.try_untrack(&Address::generate(&env), &target),
+        Err(Ok(Error::NotOwner))
+    );
+    client.untrack(&owner, &target);
+    assert_eq!(client.try_tracked(&target), Err(Ok(Error::NotTracked)));
+    assert_eq!(client.tracked_count(), 0);
+    assert_eq!(
+        client.try_untrack(&owner, &target),
+        Err(Ok(Error::NotTracked))
+    );
+}
+
+#[test]
+fn upcoming() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        owner,
+        ..
+    } = tracker(&env);
+    let first = Target::Contract(client.address.clone());
+    client.track(&owner, &first);
+    pass_ledgers(&env, 10);
+    let second = Target::Contract(token.address.clone());
+    client.track(&owner, &second);
+    assert!(client.upcoming(&100, &0, &10).is_empty());
+
+    pass_ledgers(&env, 905);
+    let upcoming = client.upcoming(&100, &0, &10);
+    assert_eq!(
+        targets(&upcoming),
+        Vec::from_array(&env, [first.clone(), second.clone()])
+    );
+
+    // Extending the first entry moves it behind the second
+    client.extend(&owner, &first);
+    assert_eq!(
+        targets(&client.upcoming(&1000, &0, &10)),
+        Vec::from_array(&env, [second, first])
+    );
+}
+
+#[test]
+fn upcoming_pages() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        owner,
+        ..
+    } = tracker(&env);
+    let first = Target::Contract(client.address.clone());
+    let second = Target::Contract(token.address.clone());
+    client.track(&owner, &first);
+    client.track(&owner, &second);
+    assert_eq!(client.tracked_count(), 2);
+
+    assert_eq!(
+        targets(&client.upcoming(&1000, &0, &1)),
+        Vec::from_array(&env, [first.clone()])
+    );
+    assert_eq!(
+        targets(&client.upcoming(&1000, &1, &1)),
+        Vec::from_array(&env, [second.clone()])
+    );
+    assert!(client.upcoming(&1000, &2, &1).is_empty());
+
+    // Untracking the first entry moves the last one into its place
+    client.untrack(&owner, &first);
+    assert_eq!(client.tracked(&second).index, 0);
+    assert_eq!(
+        targets(&client.upcoming(&1000, &0, &10)),
+        Vec::from_array(&env, [second])
+    );
+}
+
+#[test]
+fn init_twice() {
+    let env = Env::default();
+    let Setup { client, token, .. } = tracker(&env);
+    assert_eq!(
+        client.try_tracker_init(&TrackerConfig {
+            token: token.address,
+            reward: 10,
+            threshold: 100,
+            extend_to: 1000,
+            min_deposit: 100,
+        }),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn init_invalid_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client =
+        SorobanContract__Client::new(&env, &env.register_contract(None, SorobanContract__));
+    let config = TrackerConfig {
+        token: Address::generate(&env),
+        reward: 10,
+        threshold: 100,
+        extend_to: 1000,
+        min_deposit: 100,
+    };
+    assert_eq!(
+        client.try_tracker_init(&config),
+        Err(Ok(Error::NoCoreAdmin))
+    );
+    client.admin_set(&Address::generate(&env));
+    for config in [
+        TrackerConfig {
+            reward: -1,
+            ..config.clone()
+        },
+        TrackerConfig {
+            min_deposit: -1,
+            ..config.clone()
+        },
+        TrackerConfig {
+            threshold: 1000,
+            ..config.clone()
+        },
+        TrackerConfig {
+            extend_to: env.storage().max_ttl() + 1,
+            ..config.clone()
+        },
+    ] {
+        assert_eq!(
+            client.try_tracker_init(&config),
+            Err(Ok(Error::InvalidConfig))
+        );
+    }
+}
+
+#[test]
+fn extend_beyond_max_ttl() {
+    let env = Env::default();
+    let Setup {
+        client,
+        owner,
+        keeper,
+        ..
+    } = tracker(&env);
+    let target = Target::Contract(client.address.clone());
+    client.track(&owner, &target);
+
+    // The network lowers its maximum TTL below the extension
+    env.ledger().with_mut(|ledger| {
+        ledger.sequence_number += 950;
+        ledger.max_entry_ttl = 500;
+    });
+    assert_eq!(
+        client.try_extend(&keeper, &target),
+        Err(Ok(Error::InvalidConfig))
+    );
+    assert_eq!(client.deposit_of(&owner), 500);
+}
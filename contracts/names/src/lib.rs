@@ -0,0 +1,23 @@
+#![no_std]
+//! Names that packages are published to the Package Manager and contracts are deployed under with
+//! the Contract Registry.
+
+use loam_sdk::soroban_sdk::{env, Bytes, String};
+
+/// Longest name allowed, in bytes
+pub const MAX_LEN: usize = 64;
+
+/// Returns the bytes of `name` if it is 1 to 64 characters of `a-z`, `0-9`, `-` and `_`
+#[must_use]
+pub fn checked_bytes(name: &String) -> Option<Bytes> {
+    let mut buf = [0u8; MAX_LEN];
+    let len = name.len() as usize;
+    if len == 0 || len > buf.len() {
+        return None;
+    }
+    name.copy_into_slice(&mut buf[..len]);
+    let name = &buf[..len];
+    name.iter()
+        .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || *b == b'-' || *b == b'_')
+        .then(|| Bytes::from_slice(env(), name))
+}
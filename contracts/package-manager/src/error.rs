@@ -0,0 +1,20 @@
+use loam_sdk::soroban_sdk::{self, contracterror};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// No package has been published with this name
+    NoSuchPackage = 1,
+    /// The package has no release with this version
+    NoSuchVersion = 2,
+    /// Only the owner of a name can publish new versions of it
+    NotOwner = 3,
+    /// Versions must be semver: `MAJOR.MINOR.PATCH`, optionally followed by `-PRE.RELEASE` and
+    /// `+BUILD`
+    InvalidVersion = 4,
+    /// A new version must be greater than every published version of its `MAJOR.MINOR` line
+    VersionNotNewer = 5,
+    /// Names are 1 to 64 characters of `a-z`, `0-9`, `-` and `_`
+    InvalidName = 6,
+}
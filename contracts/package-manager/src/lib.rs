@@ -0,0 +1,17 @@
+#![no_std]
+use loam_sdk::derive_contract;
+use loam_subcontract_core::{admin::Admin, Core};
+
+pub mod error;
+mod storage;
+pub mod subcontract;
+mod version;
+
+pub use error::Error;
+pub use subcontract::Release;
+use subcontract::{PackageManager, Packages};
+
+#[derive_contract(Core(Admin), Packages(PackageManager))]
+pub struct Contract;
+
+mod test;
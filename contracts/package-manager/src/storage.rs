@@ -0,0 +1,59 @@
+pub(crate) use loam_sdk::soroban_sdk::ttl::extend_instance;
+use loam_sdk::soroban_sdk::ttl::DAY_IN_LEDGERS;
+use loam_sdk::soroban_sdk::{self, contracttype, env, Address, String, Vec};
+
+use crate::Release;
+
+pub(crate) const PACKAGE_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+pub(crate) const PACKAGE_LIFETIME_THRESHOLD: u32 = PACKAGE_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub(crate) enum StorageKey {
+    Owner(String),
+    Versions(String),
+    Release(String, String),
+}
+
+fn get<V: soroban_sdk::TryFromVal<soroban_sdk::Env, soroban_sdk::Val>>(
+    key: &StorageKey,
+) -> Option<V> {
+    let storage = env().storage().persistent();
+    let value = storage.get(key)?;
+    storage.extend_ttl(key, PACKAGE_LIFETIME_THRESHOLD, PACKAGE_BUMP_AMOUNT);
+    Some(value)
+}
+
+fn set<V: soroban_sdk::IntoVal<soroban_sdk::Env, soroban_sdk::Val>>(key: &StorageKey, value: &V) {
+    let storage = env().storage().persistent();
+    storage.set(key, value);
+    storage.extend_ttl(key, PACKAGE_LIFETIME_THRESHOLD, PACKAGE_BUMP_AMOUNT);
+}
+
+pub(crate) fn read_owner(name: &String) -> Option<Address> {
+    get(&StorageKey::Owner(name.clone()))
+}
+
+pub(crate) fn write_owner(name: &String, owner: &Address) {
+    set(&StorageKey::Owner(name.clone()), owner);
+}
+
+/// Versions of a package, in the order they were published
+pub(crate) fn read_versions(name: &String) -> Option<Vec<String>> {
+    get(&StorageKey::Versions(name.clone()))
+}
+
+pub(crate) fn write_versions(name: &String, versions: &Vec<String>) {
+    set(&StorageKey::Versions(name.clone()), versions);
+}
+
+pub(crate) fn read_release(name: &String, version: &String) -> Option<Release> {
+    get(&StorageKey::Release(name.clone(), version.clone()))
+}
+
+pub(crate) fn write_release(name: &String, release: &Release) {
+    set(
+        &StorageKey::Release(name.clone(), release.version.clone()),
+        release,
+    );
+}
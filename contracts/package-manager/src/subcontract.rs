@@ -0,0 +1,166 @@
+use loam_sdk::{
+    soroban_sdk::{self, contracttype, env, Address, BytesN, Lazy, Map, String, Vec},
+    subcontract,
+};
+
+use crate::{storage, version::Version, Error};
+
+/// A published version of a package
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Release {
+    pub version: String,
+    pub wasm_hash: BytesN<32>,
+    pub author: Address,
+    pub repo: String,
+    pub metadata: Map<String, String>,
+    /// Ledger in which the release was published
+    pub ledger: u32,
+}
+
+#[derive(Lazy, Default)]
+pub struct PackageManager;
+
+#[subcontract]
+pub trait IsPackages {
+    /// Publish a new version of a package, pointing to Wasm that has already been uploaded.
+    /// The first author to publish a name owns it, and only the owner can publish new versions,
+    /// each greater than those already published with the same `MAJOR.MINOR`.
+    #[allow(clippy::missing_errors_doc)]
+    fn publish(
+        &mut self,
+        author: loam_sdk::soroban_sdk::Address,
+        name: loam_sdk::soroban_sdk::String,
+        version: loam_sdk::soroban_sdk::String,
+        wasm_hash: loam_sdk::soroban_sdk::BytesN<32>,
+        repo: loam_sdk::soroban_sdk::String,
+        metadata: loam_sdk::soroban_sdk::Map<
+            loam_sdk::soroban_sdk::String,
+            loam_sdk::soroban_sdk::String,
+        >,
+    ) -> Result<(), crate::Error>;
+
+    /// Look up a release of a package, or its latest release if no version is given
+    #[allow(clippy::missing_errors_doc)]
+    fn fetch(
+        &self,
+        name: loam_sdk::soroban_sdk::String,
+        version: Option<loam_sdk::soroban_sdk::String>,
+    ) -> Result<crate::Release, crate::Error>;
+
+    /// Look up the Wasm hash of a release, or of the latest release if no version is given
+    #[allow(clippy::missing_errors_doc)]
+    fn fetch_hash(
+        &self,
+        name: loam_sdk::soroban_sdk::String,
+        version: Option<loam_sdk::soroban_sdk::String>,
+    ) -> Result<loam_sdk::soroban_sdk::BytesN<32>, crate::Error>;
+
+    /// All published versions of a package, in the order they were published
+    #[allow(clippy::missing_errors_doc)]
+    fn versions(
+        &self,
+        name: loam_sdk::soroban_sdk::String,
+    ) -> Result<loam_sdk::soroban_sdk::Vec<loam_sdk::soroban_sdk::String>, crate::Error>;
+
+    /// The address allowed to publish new versions of a package
+    fn owner(&self, name: loam_sdk::soroban_sdk::String) -> Option<loam_sdk::soroban_sdk::Address>;
+
+    /// Hand a package over to a new owner. Only the current owner can do this
+    #[allow(clippy::missing_errors_doc)]
+    fn transfer_name(
+        &mut self,
+        name: loam_sdk::soroban_sdk::String,
+        new_owner: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), crate::Error>;
+}
+
+impl IsPackages for PackageManager {
+    fn publish(
+        &mut self,
+        author: Address,
+        name: String,
+        version: String,
+        wasm_hash: BytesN<32>,
+        repo: String,
+        metadata: Map<String, String>,
+    ) -> Result<(), Error> {
+        author.require_auth();
+        loam_names::checked_bytes(&name).ok_or(Error::InvalidName)?;
+        let new_version = Version::try_from(&version)?;
+        let mut versions = match storage::read_owner(&name) {
+            Some(owner) if owner != author => return Err(Error::NotOwner),
+            Some(_) => storage::read_versions(&name).unwrap_or_else(|| Vec::new(env())),
+            None => {
+                storage::write_owner(&name, &author);
+                Vec::new(env())
+            }
+        };
+        for published in versions.iter() {
+            let published = Version::try_from(&published)?;
+            if published.same_line(&new_version) && new_version <= published {
+                return Err(Error::VersionNotNewer);
+            }
+        }
+        storage::extend_instance();
+        versions.push_back(version.clone());
+        storage::write_versions(&name, &versions);
+        storage::write_release(
+            &name,
+            &Release {
+                version,
+                wasm_hash,
+                author,
+                repo,
+                metadata,
+                ledger: env().ledger().sequence(),
+            },
+        );
+        Ok(())
+    }
+
+    fn fetch(&self, name: String, version: Option<String>) -> Result<Release, Error> {
+        let version = match version {
+            Some(version) => version,
+            None => latest(&self.versions(name.clone())?)?.ok_or(Error::NoSuchPackage)?,
+        };
+        storage::read_release(&name, &version).ok_or(Error::NoSuchVersion)
+    }
+
+    fn fetch_hash(&self, name: String, version: Option<String>) -> Result<BytesN<32>, Error> {
+        Ok(self.fetch(name, version)?.wasm_hash)
+    }
+
+    fn versions(&self, name: String) -> Result<Vec<String>, Error> {
+        storage::read_versions(&name).ok_or(Error::NoSuchPackage)
+    }
+
+    fn owner(&self, name: String) -> Option<Address> {
+        storage::read_owner(&name)
+    }
+
+    fn transfer_name(&mut self, name: String, new_owner: Address) -> Result<(), Error> {
+        let owner = storage::read_owner(&name).ok_or(Error::NoSuchPackage)?;
+        owner.require_auth();
+        storage::write_owner(&name, &new_owner);
+        Ok(())
+    }
+}
+
+/// The greatest of `versions`, leaving out pre-releases unless there are only pre-releases
+fn latest(versions: &Vec<String>) -> Result<Option<String>, Error> {
+    let mut latest: Option<(Version, String)> = None;
+    for version in versions.iter() {
+        let parsed = Version::try_from(&version)?;
+        let newer = match &latest {
+            Some((latest, _)) => {
+                (!parsed.is_pre_release(), parsed) > (!latest.is_pre_release(), *latest)
+            }
+            None => true,
+        };
+        if newer {
+            latest = Some((parsed, version));
+        }
+    }
+    Ok(latest.map(|(_, version)| version))
+}
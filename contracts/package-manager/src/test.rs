@@ -0,0 +1,235 @@
+#![cfg(test)]
+extern crate std;
+use loam_sdk::soroban_sdk::{testutils::Address as _, Address, BytesN, Env, Map, String, Vec};
+
+use crate::{Error, SorobanContract__, SorobanContract__Client};
+
+fn package_manager(env: &Env) -> SorobanContract__Client<'static> {
+    env.mock_all_auths();
+    SorobanContract__Client::new(env, &env.register_contract(None, SorobanContract__))
+}
+
+fn name(env: &Env) -> String {
+    String::from_str(env, "hello-world")
+}
+
+fn repo(env: &Env) -> String {
+    String::from_str(env, "https://github.com/loambuild/loam")
+}
+
+fn hash(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+/// Publishes `version` of `hello-world` by `author`
+fn publish(
+    client: &SorobanContract__Client,
+    author: &Address,
+    version: &str,
+    hash: &BytesN<32>,
+) -> Result<(), Error> {
+    let env = &client.env;
+    match client.try_publish(
+        author,
+        &name(env),
+        &String::from_str(env, version),
+        hash,
+        &repo(env),
+        &Map::new(env),
+    ) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(error.unwrap()),
+    }
+}
+
+#[test]
+fn publish_and_fetch() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    let v1 = String::from_str(&env, "0.1.0");
+
+    publish(&client, &author, "0.1.0", &hash(&env, 1)).unwrap();
+    publish(&client, &author, "0.10.0", &hash(&env, 2)).unwrap();
+    assert_eq!(client.owner(&name(&env)), Some(author.clone()));
+    assert_eq!(client.fetch_hash(&name(&env), &None), hash(&env, 2));
+    assert_eq!(
+        client.fetch_hash(&name(&env), &Some(v1.clone())),
+        hash(&env, 1)
+    );
+    let release = client.fetch(&name(&env), &Some(v1.clone()));
+    assert_eq!(release.author, author);
+    assert_eq!(release.repo, repo(&env));
+    assert_eq!(
+        client.versions(&name(&env)),
+        loam_sdk::soroban_sdk::vec![&env, v1, String::from_str(&env, "0.10.0")]
+    );
+}
+
+#[test]
+fn transfer_name() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    let other = Address::generate(&env);
+    publish(&client, &author, "0.1.0", &hash(&env, 1)).unwrap();
+
+    client.transfer_name(&name(&env), &other);
+    assert_eq!(client.owner(&name(&env)), Some(other.clone()));
+    assert_eq!(
+        publish(&client, &author, "0.2.0", &hash(&env, 2)),
+        Err(Error::NotOwner)
+    );
+    publish(&client, &other, "0.2.0", &hash(&env, 2)).unwrap();
+}
+
+#[test]
+fn publish_same_version() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    publish(&client, &author, "0.1.0", &hash(&env, 1)).unwrap();
+    assert_eq!(
+        publish(&client, &author, "0.1.0", &hash(&env, 2)),
+        Err(Error::VersionNotNewer)
+    );
+}
+
+#[test]
+fn publish_by_non_owner() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    publish(&client, &author, "0.1.0", &hash(&env, 1)).unwrap();
+    assert_eq!(
+        publish(&client, &Address::generate(&env), "0.2.0", &hash(&env, 2)),
+        Err(Error::NotOwner)
+    );
+}
+
+#[test]
+fn publish_invalid_version() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    for version in [
+        "1.0",
+        "1.0.0.0",
+        "01.0.0",
+        "",
+        "1.0.0-",
+        "1.0.0-01",
+        "1.0.0-rc..1",
+        "1.0.0+",
+        "1.0.0+a_b",
+    ] {
+        assert_eq!(
+            publish(&client, &author, version, &hash(&env, 1)),
+            Err(Error::InvalidVersion),
+            "{version}"
+        );
+    }
+}
+
+#[test]
+fn publish_pre_releases_and_build_metadata() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    let versions = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0+sha.5114f85",
+    ];
+    for (byte, version) in (0..).zip(versions) {
+        publish(&client, &author, version, &hash(&env, byte)).unwrap();
+    }
+    // Build metadata doesn't change a version's precedence
+    assert_eq!(
+        publish(&client, &author, "1.0.0+other", &hash(&env, 9)),
+        Err(Error::VersionNotNewer)
+    );
+    assert_eq!(
+        publish(&client, &author, "1.0.0-beta", &hash(&env, 9)),
+        Err(Error::VersionNotNewer)
+    );
+    assert_eq!(client.fetch_hash(&name(&env), &None), hash(&env, 6));
+
+    // A pre-release is only the latest while there is no release
+    publish(&client, &author, "2.0.0-rc.1", &hash(&env, 10)).unwrap();
+    assert_eq!(client.fetch_hash(&name(&env), &None), hash(&env, 6));
+}
+
+#[test]
+fn publish_patch_to_older_line() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    publish(&client, &author, "1.2.3", &hash(&env, 1)).unwrap();
+    publish(&client, &author, "2.0.0", &hash(&env, 2)).unwrap();
+    publish(&client, &author, "1.2.4", &hash(&env, 3)).unwrap();
+    assert_eq!(
+        publish(&client, &author, "1.2.2", &hash(&env, 4)),
+        Err(Error::VersionNotNewer)
+    );
+    publish(&client, &author, "1.1.9", &hash(&env, 4)).unwrap();
+    assert_eq!(client.fetch_hash(&name(&env), &None), hash(&env, 2));
+    assert_eq!(
+        client.versions(&name(&env)),
+        Vec::from_array(
+            &env,
+            ["1.2.3", "2.0.0", "1.2.4", "1.1.9"].map(|v| String::from_str(&env, v))
+        )
+    );
+}
+
+#[test]
+fn publish_invalid_name() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    let author = Address::generate(&env);
+    let too_long = "a".repeat(65);
+    for name in ["Hello", "hello world", "", &too_long] {
+        assert_eq!(
+            client.try_publish(
+                &author,
+                &String::from_str(&env, name),
+                &String::from_str(&env, "0.1.0"),
+                &hash(&env, 1),
+                &repo(&env),
+                &Map::new(&env),
+            ),
+            Err(Ok(Error::InvalidName)),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn fetch_missing() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    assert_eq!(
+        client.try_fetch(&name(&env), &None),
+        Err(Ok(Error::NoSuchPackage))
+    );
+    publish(&client, &Address::generate(&env), "0.1.0", &hash(&env, 1)).unwrap();
+    assert_eq!(
+        client.try_fetch(&name(&env), &Some(String::from_str(&env, "9.9.9"))),
+        Err(Ok(Error::NoSuchVersion))
+    );
+}
+
+#[test]
+fn transfer_missing_name() {
+    let env = Env::default();
+    let client = package_manager(&env);
+    assert_eq!(
+        client.try_transfer_name(&name(&env), &Address::generate(&env)),
+        Err(Ok(Error::NoSuchPackage))
+    );
+}
@@ -0,0 +1,160 @@
+use core::cmp::Ordering;
+
+use loam_sdk::soroban_sdk::String;
+
+use crate::Error;
+
+/// Longest version accepted, in bytes
+const MAX_LEN: usize = 64;
+
+/// A semver `MAJOR.MINOR.PATCH` version, with optional `-PRE.RELEASE` and `+BUILD` parts, ordered
+/// by precedence: build metadata is ignored, and a pre-release comes before its release
+#[derive(Clone, Copy, Debug)]
+pub struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+    /// Pre-release identifiers, without the leading `-`; empty for a release
+    pre: [u8; MAX_LEN],
+    pre_len: usize,
+}
+
+impl Version {
+    fn pre(&self) -> &[u8] {
+        &self.pre[..self.pre_len]
+    }
+
+    pub fn is_pre_release(&self) -> bool {
+        self.pre_len > 0
+    }
+
+    /// Whether `other` has the same `MAJOR.MINOR`, such as a patch of this version
+    pub fn same_line(&self, other: &Self) -> bool {
+        (self.major, self.minor) == (other.major, other.minor)
+    }
+}
+
+impl TryFrom<&String> for Version {
+    type Error = Error;
+
+    fn try_from(version: &String) -> Result<Self, Error> {
+        let mut buf = [0u8; MAX_LEN];
+        let len = version.len() as usize;
+        if len == 0 || len > buf.len() {
+            return Err(Error::InvalidVersion);
+        }
+        version.copy_into_slice(&mut buf[..len]);
+        let (version, build) = split_once(&buf[..len], b'+');
+        if build.is_some_and(|build| !build.split(|b| *b == b'.').all(is_identifier)) {
+            return Err(Error::InvalidVersion);
+        }
+        let (core, pre) = split_once(version, b'-');
+        if pre.is_some_and(|pre| !pre.split(|b| *b == b'.').all(is_pre_release_identifier)) {
+            return Err(Error::InvalidVersion);
+        }
+        let pre = pre.unwrap_or_default();
+        let mut parts = core.split(|b| *b == b'.').map(parse_number);
+        let (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(Error::InvalidVersion);
+        };
+        let mut version = Version {
+            major,
+            minor,
+            patch,
+            pre: [0; MAX_LEN],
+            pre_len: pre.len(),
+        };
+        version.pre[..pre.len()].copy_from_slice(pre);
+        Ok(version)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre(), other.pre()) {
+                ([], []) => Ordering::Equal,
+                ([], _) => Ordering::Greater,
+                (_, []) => Ordering::Less,
+                (pre, other) => {
+                    let mut ids = pre.split(|b| *b == b'.');
+                    let mut other_ids = other.split(|b| *b == b'.');
+                    loop {
+                        match (ids.next(), other_ids.next()) {
+                            (None, None) => return Ordering::Equal,
+                            (None, Some(_)) => return Ordering::Less,
+                            (Some(_), None) => return Ordering::Greater,
+                            (Some(id), Some(other)) => match compare_identifiers(id, other) {
+                                Ordering::Equal => {}
+                                ordering => return ordering,
+                            },
+                        }
+                    }
+                }
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+fn split_once(bytes: &[u8], separator: u8) -> (&[u8], Option<&[u8]>) {
+    match bytes.iter().position(|b| *b == separator) {
+        Some(i) => (&bytes[..i], Some(&bytes[i + 1..])),
+        None => (bytes, None),
+    }
+}
+
+/// A non-empty identifier of `0-9`, `A-Z`, `a-z` and `-`
+fn is_identifier(id: &[u8]) -> bool {
+    !id.is_empty() && id.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-')
+}
+
+fn is_numeric(id: &[u8]) -> bool {
+    id.iter().all(u8::is_ascii_digit)
+}
+
+/// An identifier that, if numeric, has no leading zeros
+fn is_pre_release_identifier(id: &[u8]) -> bool {
+    is_identifier(id) && !(is_numeric(id) && id.len() > 1 && id[0] == b'0')
+}
+
+/// Numeric identifiers compare as numbers and before alphanumeric ones, which compare in ASCII
+/// order
+fn compare_identifiers(id: &[u8], other: &[u8]) -> Ordering {
+    match (is_numeric(id), is_numeric(other)) {
+        // Without leading zeros, a longer number is a larger one
+        (true, true) => id.len().cmp(&other.len()).then_with(|| id.cmp(other)),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => id.cmp(other),
+    }
+}
+
+/// Parses a number without leading zeros, as required by semver
+fn parse_number(digits: &[u8]) -> Option<u32> {
+    if digits.is_empty() || (digits.len() > 1 && digits[0] == b'0') {
+        return None;
+    }
+    digits.iter().try_fold(0u32, |n, digit| {
+        if digit.is_ascii_digit() {
+            n.checked_mul(10)?.checked_add(u32::from(digit - b'0'))
+        } else {
+            None
+        }
+    })
+}
@@ -0,0 +1,17 @@
+use loam_sdk::soroban_sdk::{self, contracterror};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// The Package Manager has not been set with `set_package_manager`
+    NoPackageManager = 1,
+    /// The contract's Core admin must be set before setting the Package Manager
+    NoCoreAdmin = 2,
+    /// A contract has already been deployed with this name
+    NameTaken = 3,
+    /// No contract has been deployed with this name
+    NoSuchName = 4,
+    /// Names are 1 to 64 characters of `a-z`, `0-9`, `-` and `_`
+    InvalidName = 5,
+}
@@ -0,0 +1,17 @@
+#![no_std]
+use loam_sdk::derive_contract;
+use loam_subcontract_core::{admin::Admin, Core};
+
+pub mod error;
+mod package_manager;
+mod storage;
+pub mod subcontract;
+
+pub use error::Error;
+pub use subcontract::Deployment;
+use subcontract::{ContractRegistry, Registry};
+
+#[derive_contract(Core(Admin), Registry(ContractRegistry))]
+pub struct Contract;
+
+mod test;
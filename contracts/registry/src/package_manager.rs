@@ -0,0 +1,9 @@
+//! The part of the Package Manager's interface used by the registry, so it can be called without
+//! depending on its Wasm.
+use loam_sdk::soroban_sdk::{contractclient, BytesN, Env, String};
+
+#[allow(dead_code)]
+#[contractclient(crate_path = "loam_sdk::soroban_sdk", name = "PackageManagerClient")]
+pub trait PackageManager {
+    fn fetch_hash(env: Env, name: String, version: Option<String>) -> BytesN<32>;
+}
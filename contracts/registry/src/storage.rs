@@ -0,0 +1,48 @@
+pub(crate) use loam_sdk::soroban_sdk::ttl::extend_instance;
+use loam_sdk::soroban_sdk::ttl::DAY_IN_LEDGERS;
+use loam_sdk::soroban_sdk::{self, contracttype, env, Address, String};
+
+use crate::Deployment;
+
+pub(crate) const NAME_BUMP_AMOUNT: u32 = 90 * DAY_IN_LEDGERS;
+pub(crate) const NAME_LIFETIME_THRESHOLD: u32 = NAME_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub(crate) enum StorageKey {
+    PackageManager,
+    Deployment(String),
+}
+
+pub(crate) fn read_package_manager() -> Option<Address> {
+    env().storage().instance().get(&StorageKey::PackageManager)
+}
+
+pub(crate) fn write_package_manager(package_manager: &Address) {
+    env()
+        .storage()
+        .instance()
+        .set(&StorageKey::PackageManager, package_manager);
+}
+
+pub(crate) fn has_deployment(name: &String) -> bool {
+    env()
+        .storage()
+        .persistent()
+        .has(&StorageKey::Deployment(name.clone()))
+}
+
+pub(crate) fn read_deployment(name: &String) -> Option<Deployment> {
+    let key = StorageKey::Deployment(name.clone());
+    let storage = env().storage().persistent();
+    let deployment = storage.get(&key)?;
+    storage.extend_ttl(&key, NAME_LIFETIME_THRESHOLD, NAME_BUMP_AMOUNT);
+    Some(deployment)
+}
+
+pub(crate) fn write_deployment(name: &String, deployment: &Deployment) {
+    let key = StorageKey::Deployment(name.clone());
+    let storage = env().storage().persistent();
+    storage.set(&key, deployment);
+    storage.extend_ttl(&key, NAME_LIFETIME_THRESHOLD, NAME_BUMP_AMOUNT);
+}
@@ -0,0 +1,163 @@
+use loam_sdk::{
+    soroban_sdk::{
+        self, contracttype, env, symbol_short, Address, BytesN, IntoVal, Lazy, String, Symbol, Val,
+        Vec,
+    },
+    subcontract,
+};
+use loam_subcontract_core::admin::{Admin, IsCore};
+
+use crate::{package_manager::PackageManagerClient, storage, Error};
+
+/// A contract deployed through the registry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deployment {
+    pub contract_id: Address,
+    /// Address allowed to redeploy the contract through the registry
+    pub owner: Address,
+    /// Package Manager name of the deployed Wasm
+    pub package: String,
+    pub wasm_hash: BytesN<32>,
+}
+
+#[derive(Lazy, Default)]
+pub struct ContractRegistry;
+
+#[subcontract]
+pub trait IsRegistry {
+    /// Set the Package Manager contract that Wasm hashes are looked up in. Requires the auth of the
+    /// Core admin
+    #[allow(clippy::missing_errors_doc)]
+    fn set_package_manager(
+        &mut self,
+        package_manager: loam_sdk::soroban_sdk::Address,
+    ) -> Result<(), crate::Error>;
+
+    /// The Package Manager contract that Wasm hashes are looked up in
+    #[allow(clippy::missing_errors_doc)]
+    fn package_manager(&self) -> Result<loam_sdk::soroban_sdk::Address, crate::Error>;
+
+    /// Deploy a published package under a unique name, then call `init_fn` with `init_args` on
+    /// the new contract, all in one transaction. This lets a contract's admin be set as it is
+    /// deployed, so it cannot be front-run. Leaving out the version deploys the latest release.
+    #[allow(clippy::missing_errors_doc, clippy::too_many_arguments)]
+    fn deploy(
+        &mut self,
+        owner: loam_sdk::soroban_sdk::Address,
+        name: loam_sdk::soroban_sdk::String,
+        package: loam_sdk::soroban_sdk::String,
+        version: Option<loam_sdk::soroban_sdk::String>,
+        init_fn: Option<loam_sdk::soroban_sdk::Symbol>,
+        init_args: loam_sdk::soroban_sdk::Vec<loam_sdk::soroban_sdk::Val>,
+    ) -> Result<loam_sdk::soroban_sdk::Address, crate::Error>;
+
+    /// Look up the contract ID deployed with a name
+    #[allow(clippy::missing_errors_doc)]
+    fn fetch_contract_id(
+        &self,
+        name: loam_sdk::soroban_sdk::String,
+    ) -> Result<loam_sdk::soroban_sdk::Address, crate::Error>;
+
+    /// Look up the contract ID, owner, package and Wasm hash deployed with a name
+    #[allow(clippy::missing_errors_doc)]
+    fn fetch_deployment(
+        &self,
+        name: loam_sdk::soroban_sdk::String,
+    ) -> Result<crate::Deployment, crate::Error>;
+
+    /// Redeploy a named contract to another release of its package, by calling the contract's
+    /// `redeploy` with the release's Wasm hash. Requires the auth of the name's owner, who must
+    /// also be the contract's Core admin. Leaving out the version redeploys the latest release.
+    #[allow(clippy::missing_errors_doc)]
+    fn redeploy_contract(
+        &mut self,
+        name: loam_sdk::soroban_sdk::String,
+        version: Option<loam_sdk::soroban_sdk::String>,
+    ) -> Result<(), crate::Error>;
+}
+
+/// Checks that a name is 1 to 64 characters of `a-z`, `0-9`, `-` and `_`, and returns the salt the
+/// contract is deployed with, so a name always maps to the same contract ID
+fn name_salt(name: &String) -> Result<BytesN<32>, Error> {
+    let name = loam_names::checked_bytes(name).ok_or(Error::InvalidName)?;
+    Ok(env().crypto().sha256(&name).into())
+}
+
+fn package_manager() -> Result<PackageManagerClient<'static>, Error> {
+    let id = storage::read_package_manager().ok_or(Error::NoPackageManager)?;
+    Ok(PackageManagerClient::new(env(), &id))
+}
+
+impl IsRegistry for ContractRegistry {
+    fn set_package_manager(&mut self, package_manager: Address) -> Result<(), Error> {
+        let admin = Admin::get_lazy()
+            .and_then(|admin| admin.admin_get())
+            .ok_or(Error::NoCoreAdmin)?;
+        admin.require_auth();
+        storage::write_package_manager(&package_manager);
+        Ok(())
+    }
+
+    fn package_manager(&self) -> Result<Address, Error> {
+        storage::read_package_manager().ok_or(Error::NoPackageManager)
+    }
+
+    fn deploy(
+        &mut self,
+        owner: Address,
+        name: String,
+        package: String,
+        version: Option<String>,
+        init_fn: Option<Symbol>,
+        init_args: Vec<Val>,
+    ) -> Result<Address, Error> {
+        owner.require_auth();
+        let salt = name_salt(&name)?;
+        if storage::has_deployment(&name) {
+            return Err(Error::NameTaken);
+        }
+        let wasm_hash = package_manager()?.fetch_hash(&package, &version);
+        let contract_id = env()
+            .deployer()
+            .with_current_contract(salt)
+            .deploy(wasm_hash.clone());
+        if let Some(init_fn) = init_fn {
+            env().invoke_contract::<Val>(&contract_id, &init_fn, init_args);
+        }
+        storage::extend_instance();
+        storage::write_deployment(
+            &name,
+            &Deployment {
+                contract_id: contract_id.clone(),
+                owner,
+                package,
+                wasm_hash,
+            },
+        );
+        Ok(contract_id)
+    }
+
+    fn fetch_contract_id(&self, name: String) -> Result<Address, Error> {
+        Ok(self.fetch_deployment(name)?.contract_id)
+    }
+
+    fn fetch_deployment(&self, name: String) -> Result<Deployment, Error> {
+        storage::read_deployment(&name).ok_or(Error::NoSuchName)
+    }
+
+    fn redeploy_contract(&mut self, name: String, version: Option<String>) -> Result<(), Error> {
+        let mut deployment = self.fetch_deployment(name.clone())?;
+        deployment.owner.require_auth();
+        let wasm_hash = package_manager()?.fetch_hash(&deployment.package, &version);
+        env().invoke_contract::<()>(
+            &deployment.contract_id,
+            &symbol_short!("redeploy"),
+            (wasm_hash.clone(),).into_val(env()),
+        );
+        storage::extend_instance();
+        deployment.wasm_hash = wasm_hash;
+        storage::write_deployment(&name, &deployment);
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+#![cfg(test)]
+extern crate std;
+use loam_sdk::soroban_sdk::{
+    testutils::Address as _, vec, Address, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
+};
+
+use crate::{Deployment, Error, SorobanContract__, SorobanContract__Client};
+
+/// A contract built into `target/loam` by `just build`, which `just test` runs first
+fn wasm(name: &str) -> std::vec::Vec<u8> {
+    let path = std::format!(
+        "{}/../../target/loam/{name}.wasm",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("{path}: {e}; build the contracts with `just build` first"))
+}
+
+struct Setup {
+    client: SorobanContract__Client<'static>,
+    owner: Address,
+    /// Hash of `hello-world` 0.1.0, a contract with only the Core subcontract
+    core: BytesN<32>,
+    /// Hash of `hello-world` 0.2.0, which adds status messages
+    status_message: BytesN<32>,
+}
+
+fn package(env: &Env) -> String {
+    String::from_str(env, "hello-world")
+}
+
+/// A registry pointing to a Package Manager with two releases of `hello-world`
+fn registry(env: &Env) -> Setup {
+    env.mock_all_auths();
+    let client = SorobanContract__Client::new(env, &env.register_contract(None, SorobanContract__));
+    let package_manager = env.register_contract_wasm(None, wasm("loam_package_manager").as_slice());
+    let owner = Address::generate(env);
+    let core = env
+        .deployer()
+        .upload_contract_wasm(wasm("example_core").as_slice());
+    let status_message = env
+        .deployer()
+        .upload_contract_wasm(wasm("example_status_message").as_slice());
+    for (version, hash) in [("0.1.0", &core), ("0.2.0", &status_message)] {
+        env.invoke_contract::<()>(
+            &package_manager,
+            &Symbol::new(env, "publish"),
+            (
+                &owner,
+                package(env),
+                String::from_str(env, version),
+                hash.clone(),
+                String::from_str(env, "https://github.com/loambuild/loam"),
+                Map::<String, String>::new(env),
+            )
+                .into_val(env),
+        );
+    }
+    client.admin_set(&Address::generate(env));
+    client.set_package_manager(&package_manager);
+    Setup {
+        client,
+        owner,
+        core,
+        status_message,
+    }
+}
+
+fn admin_get(env: &Env, contract_id: &Address) -> Option<Address> {
+    env.invoke_contract(contract_id, &Symbol::new(env, "admin_get"), vec![env])
+}
+
+#[test]
+fn deploy() {
+    let env = Env::default();
+    let Setup {
+        client,
+        owner,
+        core,
+        ..
+    } = registry(&env);
+    let name = String::from_str(&env, "hello");
+
+    let contract_id = client.deploy(
+        &owner,
+        &name,
+        &package(&env),
+        &Some(String::from_str(&env, "0.1.0")),
+        &None,
+        &vec![&env],
+    );
+    assert_eq!(
+        client.fetch_deployment(&name),
+        Deployment {
+            contract_id: contract_id.clone(),
+            owner,
+            package: package(&env),
+            wasm_hash: core,
+        }
+    );
+    assert_eq!(client.fetch_contract_id(&name), contract_id);
+    assert_eq!(admin_get(&env, &contract_id), None);
+}
+
+#[test]
+fn deploy_and_init() {
+    let env = Env::default();
+    let Setup { client, owner, .. } = registry(&env);
+    let name = String::from_str(&env, "hello");
+
+    let init_args: Vec<Val> = vec![&env, owner.into_val(&env)];
+    let contract_id = client.deploy(
+        &owner,
+        &name,
+        &package(&env),
+        &Some(String::from_str(&env, "0.1.0")),
+        &Some(Symbol::new(&env, "admin_set")),
+        &init_args,
+    );
+    assert_eq!(admin_get(&env, &contract_id), Some(owner));
+}
+
+#[test]
+fn redeploy_contract() {
+    let env = Env::default();
+    let Setup {
+        client,
+        owner,
+        status_message,
+        ..
+    } = registry(&env);
+    let name = String::from_str(&env, "hello");
+    let init_args: Vec<Val> = vec![&env, owner.into_val(&env)];
+    let contract_id = client.deploy(
+        &owner,
+        &name,
+        &package(&env),
+        &Some(String::from_str(&env, "0.1.0")),
+        &Some(Symbol::new(&env, "admin_set")),
+        &init_args,
+    );
+
+    client.redeploy_contract(&name, &None);
+    let deployment = client.fetch_deployment(&name);
+    assert_eq!(deployment.contract_id, contract_id);
+    assert_eq!(deployment.wasm_hash, status_message);
+    let message: Option<String> = env.invoke_contract(
+        &contract_id,
+        &Symbol::new(&env, "messages_get"),
+        vec![&env, owner.into_val(&env)],
+    );
+    assert_eq!(message, None);
+}
+
+#[test]
+fn deploy_taken_name() {
+    let env = Env::default();
+    let Setup { client, owner, .. } = registry(&env);
+    let name = String::from_str(&env, "hello");
+    client.deploy(&owner, &name, &package(&env), &None, &None, &vec![&env]);
+    assert_eq!(
+        client.try_deploy(&owner, &name, &package(&env), &None, &None, &vec![&env]),
+        Err(Ok(Error::NameTaken))
+    );
+}
+
+#[test]
+fn deploy_invalid_name() {
+    let env = Env::default();
+    let Setup { client, owner, .. } = registry(&env);
+    assert_eq!(
+        client.try_deploy(
+            &owner,
+            &String::from_str(&env, "Hello World"),
+            &package(&env),
+            &None,
+            &None,
+            &vec![&env]
+        ),
+        Err(Ok(Error::InvalidName))
+    );
+}
+
+#[test]
+fn missing_name() {
+    let env = Env::default();
+    let Setup { client, .. } = registry(&env);
+    let name = String::from_str(&env, "hello");
+    assert_eq!(
+        client.try_fetch_contract_id(&name),
+        Err(Ok(Error::NoSuchName))
+    );
+    assert_eq!(
+        client.try_redeploy_contract(&name, &None),
+        Err(Ok(Error::NoSuchName))
+    );
+}
+
+#[test]
+fn set_package_manager_without_core_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client =
+        SorobanContract__Client::new(&env, &env.register_contract(None, SorobanContract__));
+    assert_eq!(
+        client.try_set_package_manager(&Address::generate(&env)),
+        Err(Ok(Error::NoCoreAdmin))
+    );
+    assert_eq!(
+        client.try_deploy(
+            &Address::generate(&env),
+            &String::from_str(&env, "hello"),
+            &package(&env),
+            &None,
+            &None,
+            &vec![&env]
+        ),
+        Err(Ok(Error::NoPackageManager))
+    );
+}
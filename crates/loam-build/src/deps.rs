@@ -304,10 +304,18 @@ pub fn get_workspace(packages: &[Package]) -> Result<Vec<Package>, Error> {
         graph.insert(p.id.clone());
     }
     let mut res = Vec::new();
-    while let Some(p) = graph.pop() {
-        if let Some(contract) = packages.iter().find(|p2| p2.id == p) {
-            res.push(contract.clone());
+    loop {
+        let ids = graph.pop_all();
+        if ids.is_empty() {
+            break;
         }
+        // Packages that don't depend on each other are ordered by name, so builds are repeatable
+        let mut ready = ids
+            .iter()
+            .filter_map(|id| packages.iter().find(|p| p.id == *id))
+            .collect::<Vec<_>>();
+        ready.sort_by(|a, b| a.name.cmp(&b.name));
+        res.extend(ready.into_iter().cloned());
     }
     Ok(res)
 }
@@ -4,3 +4,5 @@ use loam_subcontract_core::{admin::Admin, Core};
 
 #[derive_contract(Core(Admin))]
 pub struct Contract;
+
+mod test;
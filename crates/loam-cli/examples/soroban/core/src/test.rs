@@ -0,0 +1,15 @@
+#![cfg(test)]
+use loam_sdk::soroban_sdk::{Env, String};
+
+use crate::{SorobanContract__, SorobanContract__Client};
+
+#[test]
+fn version_is_crate_version() {
+    let env = Env::default();
+    let client =
+        SorobanContract__Client::new(&env, &env.register_contract(None, SorobanContract__));
+    assert_eq!(
+        client.version(),
+        String::from_str(&env, env!("CARGO_PKG_VERSION"))
+    );
+}
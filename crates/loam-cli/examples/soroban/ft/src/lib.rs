@@ -1,11 +1,19 @@
 #![no_std]
 use loam_sdk::derive_contract;
 use loam_subcontract_core::{admin::Admin, Core};
-use loam_subcontract_ft::{Fungible, Initable};
+use loam_subcontract_ft::{
+    Capped, Fungible, FungibleAdmin, FungibleToken, Initable, Minters, Supply,
+};
 
-pub mod ft;
-
-use ft::MyFungibleToken;
-
-#[derive_contract(Core(Admin), Fungible(MyFungibleToken), Initable(MyFungibleToken))]
+#[derive_contract(
+    Core(Admin),
+    Fungible(FungibleToken),
+    FungibleAdmin(FungibleToken),
+    Initable(FungibleToken),
+    Supply(FungibleToken),
+    Capped(FungibleToken),
+    Minters(FungibleToken)
+)]
 pub struct Contract;
+
+mod test;
@@ -0,0 +1,433 @@
+#![cfg(test)]
+extern crate std;
+
+use loam_sdk::soroban_sdk::{
+    set_env,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger},
+    Address, Env, IntoVal, String, Symbol, Val, Vec,
+};
+use loam_subcontract_ft::{FungibleToken, IsCapped, IsFungible, IsFungibleAdmin, IsMinters};
+
+use crate::{SorobanContract__, SorobanContract__Client};
+
+/// A token initialized by `admin`, with all auths mocked
+fn token(env: &Env) -> (SorobanContract__Client<'static>, Address) {
+    env.mock_all_auths();
+    let client = SorobanContract__Client::new(env, &env.register_contract(None, SorobanContract__));
+    let admin = Address::generate(env);
+    client.admin_set(&admin);
+    client.ft_init(
+        &admin,
+        &String::from_str(env, "Loam"),
+        &String::from_str(env, "LOAM"),
+        &7,
+    );
+    (client, admin)
+}
+
+/// Call the token's implementation directly, as the contract. A contract call through the client
+/// that fails aborts the test process, since soroban-sdk exports contract functions as `extern "C"`.
+fn as_token<T>(
+    env: &Env,
+    client: &SorobanContract__Client,
+    f: impl FnOnce(&mut FungibleToken) -> T,
+) -> T {
+    env.as_contract(&client.address, || {
+        set_env(env.clone());
+        f(&mut FungibleToken)
+    })
+}
+
+#[test]
+fn init_sets_metadata() {
+    let env = Env::default();
+    let (client, admin) = token(&env);
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.name(), String::from_str(&env, "Loam"));
+    assert_eq!(client.symbol(), String::from_str(&env, "LOAM"));
+    assert_eq!(client.admin(), admin);
+}
+
+#[test]
+fn transfer() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    client.mint(&user1, &1000);
+    client.transfer(&user1, &user2, &600);
+    assert_eq!(client.balance(&user1), 400);
+    assert_eq!(client.balance(&user2), 600);
+    assert_eq!(client.total_supply(), 1000);
+}
+
+#[test]
+fn spend_allowance() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    client.mint(&owner, &1000);
+    client.approve(&owner, &spender, &500, &200);
+    client.transfer_from(&spender, &owner, &spender, &100);
+    assert_eq!(client.allowance(&owner, &spender), 400);
+    client.burn_from(&spender, &owner, &100);
+    assert_eq!(client.allowance(&owner, &spender), 300);
+    assert_eq!(client.balance(&owner), 800);
+    assert_eq!(client.balance(&spender), 100);
+    assert_eq!(client.total_supply(), 900);
+}
+
+#[test]
+fn claw_back_from_deauthorized_account() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let user = Address::generate(&env);
+
+    client.mint(&user, &1000);
+    client.set_authorized(&user, &false);
+    assert!(!client.authorized(&user));
+    client.clawback(&user, &100);
+    assert_eq!(client.balance(&user), 900);
+    client.set_authorized(&user, &true);
+    assert!(client.authorized(&user));
+    assert_eq!(client.total_supply(), 900);
+}
+
+#[test]
+fn mint_by_minter_within_cap() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let user = Address::generate(&env);
+    let minter = Address::generate(&env);
+
+    client.mint(&user, &800);
+    client.set_cap(&1000);
+    assert_eq!(client.cap(), Some(1000));
+    client.set_minter(&minter, &true);
+    assert!(client.is_minter(&minter));
+    client.mint_by(&minter, &user, &200);
+    assert_eq!(client.balance(&user), 1000);
+    assert_eq!(client.total_supply(), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn transfer_negative_amount() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.mint(&from, &1000);
+    as_token(&env, &client, |token| token.transfer(from, to, -1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn approve_negative_amount() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let from = Address::generate(&env);
+    let spender = Address::generate(&env);
+    as_token(&env, &client, |token| token.approve(from, spender, -1, 200));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn mint_negative_amount() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let to = Address::generate(&env);
+    as_token(&env, &client, |token| token.mint(to, -1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn transfer_from_over_allowance() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&owner, &1000);
+    client.approve(&owner, &spender, &100, &200);
+    as_token(&env, &client, |token| {
+        token.transfer_from(spender.clone(), owner, spender, 101);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn burn_from_over_allowance() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&owner, &1000);
+    client.approve(&owner, &spender, &100, &200);
+    as_token(&env, &client, |token| token.burn_from(spender, owner, 101));
+}
+
+/// Approve `spender` to spend 100 of `owner`'s tokens until 10 ledgers from now, then move past
+/// that ledger
+fn expire_allowance(
+    env: &Env,
+    client: &SorobanContract__Client,
+    owner: &Address,
+    spender: &Address,
+) {
+    client.mint(owner, &1000);
+    let expiration_ledger = env.ledger().sequence() + 10;
+    client.approve(owner, spender, &100, &expiration_ledger);
+    env.ledger().set_sequence_number(expiration_ledger + 1);
+}
+
+#[test]
+fn expired_allowance_reads_as_zero() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    expire_allowance(&env, &client, &owner, &spender);
+    assert_eq!(client.allowance(&owner, &spender), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn transfer_from_expired_allowance() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    expire_allowance(&env, &client, &owner, &spender);
+    as_token(&env, &client, |token| {
+        token.transfer_from(spender.clone(), owner, spender, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn approve_until_past_ledger() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    env.ledger().set_sequence_number(100);
+    as_token(&env, &client, |token| {
+        token.approve(owner, spender, 100, 99);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn transfer_from_deauthorized_account() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.mint(&from, &1000);
+    client.set_authorized(&from, &false);
+    as_token(&env, &client, |token| token.transfer(from, to, 1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn transfer_to_deauthorized_account() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.mint(&from, &1000);
+    client.set_authorized(&to, &false);
+    as_token(&env, &client, |token| token.transfer(from, to, 1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn mint_to_deauthorized_account() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let to = Address::generate(&env);
+    client.set_authorized(&to, &false);
+    as_token(&env, &client, |token| token.mint(to, 1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn mint_past_cap() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let to = Address::generate(&env);
+    client.mint(&to, &800);
+    client.set_cap(&1000);
+    as_token(&env, &client, |token| token.mint(to, 201));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn mint_by_minter_past_cap() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let minter = Address::generate(&env);
+    client.set_cap(&1000);
+    client.set_minter(&minter, &true);
+    as_token(&env, &client, |token| {
+        token.mint_by(minter.clone(), minter, 1001);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn mint_by_non_minter() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let minter = Address::generate(&env);
+    as_token(&env, &client, |token| {
+        token.mint_by(minter.clone(), minter, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn mint_by_removed_minter() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    let minter = Address::generate(&env);
+    client.set_minter(&minter, &true);
+    client.set_minter(&minter, &false);
+    assert!(!client.is_minter(&minter));
+    as_token(&env, &client, |token| {
+        token.mint_by(minter.clone(), minter, 1);
+    });
+}
+
+#[test]
+fn lower_cap() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    client.mint(&Address::generate(&env), &500);
+    client.set_cap(&1000);
+    client.set_cap(&500);
+    assert_eq!(client.cap(), Some(500));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn raise_cap() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    client.set_cap(&1000);
+    as_token(&env, &client, |token| token.set_cap(1001));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn cap_below_supply() {
+    let env = Env::default();
+    let (client, _) = token(&env);
+    client.mint(&Address::generate(&env), &500);
+    as_token(&env, &client, |token| token.set_cap(499));
+}
+
+/// The auths recorded for a call of `function` that only `address` authorized
+fn authorized_by(
+    client: &SorobanContract__Client,
+    address: &Address,
+    function: &str,
+    args: Vec<Val>,
+) -> std::vec::Vec<(Address, AuthorizedInvocation)> {
+    std::vec![(
+        address.clone(),
+        AuthorizedInvocation {
+            function: AuthorizedFunction::Contract((
+                client.address.clone(),
+                Symbol::new(&client.env, function),
+                args,
+            )),
+            sub_invocations: std::vec![],
+        },
+    )]
+}
+
+#[test]
+fn spending_requires_auth() {
+    let env = Env::default();
+    let (client, admin) = token(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    client.mint(&owner, &1000);
+    assert_eq!(
+        env.auths(),
+        authorized_by(&client, &admin, "mint", (&owner, 1000_i128).into_val(&env))
+    );
+    client.transfer(&owner, &spender, &10);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &owner,
+            "transfer",
+            (&owner, &spender, 10_i128).into_val(&env)
+        )
+    );
+    client.approve(&owner, &spender, &100, &200);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &owner,
+            "approve",
+            (&owner, &spender, 100_i128, 200_u32).into_val(&env)
+        )
+    );
+    client.transfer_from(&spender, &owner, &spender, &10);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &spender,
+            "transfer_from",
+            (&spender, &owner, &spender, 10_i128).into_val(&env)
+        )
+    );
+    client.burn(&owner, &10);
+    assert_eq!(
+        env.auths(),
+        authorized_by(&client, &owner, "burn", (&owner, 10_i128).into_val(&env))
+    );
+}
+
+#[test]
+fn cap_and_minters_require_auth() {
+    let env = Env::default();
+    let (client, admin) = token(&env);
+    let minter = Address::generate(&env);
+
+    client.set_cap(&1000);
+    assert_eq!(
+        env.auths(),
+        authorized_by(&client, &admin, "set_cap", (1000_i128,).into_val(&env))
+    );
+    client.set_minter(&minter, &true);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &admin,
+            "set_minter",
+            (&minter, true).into_val(&env)
+        )
+    );
+    client.mint_by(&minter, &minter, &1);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &minter,
+            "mint_by",
+            (&minter, &minter, 1_i128).into_val(&env)
+        )
+    );
+}
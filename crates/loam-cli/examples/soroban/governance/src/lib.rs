@@ -0,0 +1,9 @@
+#![no_std]
+use loam_sdk::derive_contract;
+use loam_subcontract_core::{admin::Admin, Core};
+use loam_subcontract_governance::{Governance, Governor};
+
+#[derive_contract(Core(Admin), Governor(Governance))]
+pub struct Contract;
+
+mod test;
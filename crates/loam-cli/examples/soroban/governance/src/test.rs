@@ -0,0 +1,251 @@
+#![cfg(test)]
+use loam_sdk::soroban_sdk::{
+    set_env,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env,
+};
+use loam_subcontract_governance::{Action, Governance, GovernorConfig, IsGovernor, ProposalStatus};
+
+use crate::{SorobanContract__, SorobanContract__Client};
+
+struct Setup {
+    client: SorobanContract__Client<'static>,
+    token: TokenClient<'static>,
+    admin: Address,
+    voter1: Address,
+    voter2: Address,
+    config: GovernorConfig,
+}
+
+/// A governor handed over to its token holders, with `voter1` holding 100 tokens and `voter2` 50
+fn governor(env: &Env) -> Setup {
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, SorobanContract__);
+    let client = SorobanContract__Client::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let voter1 = Address::generate(env);
+    let voter2 = Address::generate(env);
+    let token = env.register_stellar_asset_contract(admin.clone());
+    let token_admin = StellarAssetClient::new(env, &token);
+    token_admin.mint(&voter1, &100);
+    token_admin.mint(&voter2, &50);
+
+    let config = GovernorConfig {
+        token: token.clone(),
+        quorum: 100,
+        voting_period: 10,
+        timelock: 5,
+    };
+    client.admin_set(&admin);
+    client.gov_init(&config);
+    client.admin_set(&contract_id);
+    Setup {
+        client,
+        token: TokenClient::new(env, &token),
+        admin,
+        voter1,
+        voter2,
+        config,
+    }
+}
+
+/// Call the governor's implementation directly, as the contract. A contract call through the
+/// client that fails aborts the test process, since soroban-sdk exports contract functions as
+/// `extern "C"`.
+fn as_gov<T>(
+    env: &Env,
+    client: &SorobanContract__Client,
+    f: impl FnOnce(&mut Governance) -> T,
+) -> T {
+    env.as_contract(&client.address, || {
+        set_env(env.clone());
+        f(&mut Governance)
+    })
+}
+
+fn pass_ledgers(env: &Env, ledgers: u32) {
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number += ledgers);
+}
+
+#[test]
+fn configure() {
+    let env = Env::default();
+    let Setup {
+        client,
+        voter1,
+        voter2,
+        config,
+        ..
+    } = governor(&env);
+    let new_config = GovernorConfig {
+        quorum: 120,
+        ..config
+    };
+
+    let id = client.propose(&voter1, &Action::Configure(new_config.clone()));
+    client.vote(&voter1, &id, &true);
+    client.vote(&voter2, &id, &false);
+    let proposal = client.proposal(&id);
+    assert_eq!(proposal.votes_for, 100);
+    assert_eq!(proposal.votes_against, 50);
+
+    pass_ledgers(&env, 11);
+    client.queue(&id);
+    assert_eq!(client.proposal(&id).status, ProposalStatus::Queued);
+
+    pass_ledgers(&env, 5);
+    client.execute(&id);
+    assert_eq!(client.proposal(&id).status, ProposalStatus::Executed);
+    assert_eq!(client.gov_config(), new_config);
+}
+
+#[test]
+fn set_admin() {
+    let env = Env::default();
+    let Setup {
+        client,
+        admin,
+        voter1,
+        voter2,
+        ..
+    } = governor(&env);
+
+    let id = client.propose(&voter2, &Action::SetAdmin(admin.clone()));
+    client.vote(&voter1, &id, &true);
+    client.vote(&voter2, &id, &true);
+    pass_ledgers(&env, 11);
+    client.queue(&id);
+    pass_ledgers(&env, 5);
+    client.execute(&id);
+    assert_eq!(client.admin_get(), Some(admin));
+}
+
+#[test]
+fn voting_locks_tokens_until_voting_ends() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        admin,
+        voter1,
+        ..
+    } = governor(&env);
+
+    let id = client.propose(&voter1, &Action::SetAdmin(admin));
+    client.vote(&voter1, &id, &true);
+    assert_eq!(token.balance(&voter1), 0);
+    assert_eq!(token.balance(&client.address), 100);
+
+    pass_ledgers(&env, 11);
+    client.unlock(&voter1, &id);
+    assert_eq!(token.balance(&voter1), 100);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn unlock_in_the_token_voted_with() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        admin,
+        voter1,
+        voter2,
+        config,
+    } = governor(&env);
+    let new_token = env.register_stellar_asset_contract(admin.clone());
+    StellarAssetClient::new(&env, &new_token).mint(&client.address, &50);
+
+    let locked = client.propose(&voter2, &Action::SetAdmin(admin));
+    client.vote(&voter2, &locked, &true);
+    let configure = client.propose(
+        &voter1,
+        &Action::Configure(GovernorConfig {
+            token: new_token.clone(),
+            ..config
+        }),
+    );
+    client.vote(&voter1, &configure, &true);
+    pass_ledgers(&env, 11);
+    client.queue(&configure);
+    pass_ledgers(&env, 5);
+    client.execute(&configure);
+    assert_eq!(client.gov_config().token, new_token);
+
+    client.unlock(&voter2, &locked);
+    assert_eq!(token.balance(&voter2), 50);
+    assert_eq!(TokenClient::new(&env, &new_token).balance(&voter2), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn transferred_tokens_cannot_vote_again() {
+    let env = Env::default();
+    let Setup {
+        client,
+        token,
+        admin,
+        voter1,
+        ..
+    } = governor(&env);
+    let other = Address::generate(&env);
+
+    let id = client.propose(&voter1, &Action::SetAdmin(admin));
+    client.vote(&voter1, &id, &true);
+    // The tokens are locked in the governor, so there is nothing left to hand to another voter
+    assert!(token.try_transfer(&voter1, &other, &100).is_err());
+    as_gov(&env, &client, |gov| gov.vote(other, id, true));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn vote_twice() {
+    let env = Env::default();
+    let Setup {
+        client,
+        admin,
+        voter1,
+        ..
+    } = governor(&env);
+
+    let id = client.propose(&voter1, &Action::SetAdmin(admin));
+    client.vote(&voter1, &id, &true);
+    as_gov(&env, &client, |gov| gov.vote(voter1, id, false));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn unlock_while_voting() {
+    let env = Env::default();
+    let Setup {
+        client,
+        admin,
+        voter1,
+        ..
+    } = governor(&env);
+
+    let id = client.propose(&voter1, &Action::SetAdmin(admin));
+    client.vote(&voter1, &id, &true);
+    as_gov(&env, &client, |gov| gov.unlock(voter1, id));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn unlock_twice() {
+    let env = Env::default();
+    let Setup {
+        client,
+        admin,
+        voter1,
+        ..
+    } = governor(&env);
+
+    let id = client.propose(&voter1, &Action::SetAdmin(admin));
+    client.vote(&voter1, &id, &true);
+    pass_ledgers(&env, 11);
+    client.unlock(&voter1, &id);
+    as_gov(&env, &client, |gov| gov.unlock(voter1, id));
+}
@@ -0,0 +1,14 @@
+#![no_std]
+use loam_sdk::derive_contract;
+use loam_subcontract_core::{admin::Admin, Core};
+use loam_subcontract_nft::{Burnable, Mintable, NonFungible, NonFungibleToken};
+
+#[derive_contract(
+    Core(Admin),
+    NonFungible(NonFungibleToken),
+    Mintable(NonFungibleToken),
+    Burnable(NonFungibleToken)
+)]
+pub struct Contract;
+
+mod test;
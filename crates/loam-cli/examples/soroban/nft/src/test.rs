@@ -0,0 +1,278 @@
+#![cfg(test)]
+extern crate std;
+
+use loam_sdk::soroban_sdk::{
+    set_env,
+    testutils::{
+        storage::Persistent as _, Address as _, AuthorizedFunction, AuthorizedInvocation, Ledger,
+    },
+    ttl::DAY_IN_LEDGERS,
+    Address, Env, IntoVal, String, Symbol, Val, Vec,
+};
+use loam_subcontract_nft::{IsMintable, IsNonFungible, NonFungibleToken};
+
+use crate::{SorobanContract__, SorobanContract__Client};
+
+/// A token contract with its Core admin set and all auths mocked
+fn nft(env: &Env) -> SorobanContract__Client<'static> {
+    env.mock_all_auths();
+    let client = SorobanContract__Client::new(env, &env.register_contract(None, SorobanContract__));
+    client.admin_set(&Address::generate(env));
+    client
+}
+
+fn uri(env: &Env) -> String {
+    String::from_str(env, "ipfs://token/1")
+}
+
+/// Call the token's implementation directly, as the contract. A contract call through the client
+/// that fails aborts the test process, since soroban-sdk exports contract functions as `extern "C"`.
+fn as_nft<T>(
+    env: &Env,
+    client: &SorobanContract__Client,
+    f: impl FnOnce(&mut NonFungibleToken) -> T,
+) -> T {
+    env.as_contract(&client.address, || {
+        set_env(env.clone());
+        f(&mut NonFungibleToken)
+    })
+}
+
+#[test]
+fn mint() {
+    let env = Env::default();
+    let client = nft(&env);
+    let user = Address::generate(&env);
+
+    client.mint(&user, &1, &uri(&env));
+    client.mint(&user, &2, &uri(&env));
+    assert_eq!(client.owner_of(&1), user);
+    assert_eq!(client.token_uri(&1), uri(&env));
+    assert_eq!(client.balance(&user), 2);
+}
+
+#[test]
+fn transfer() {
+    let env = Env::default();
+    let client = nft(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    client.mint(&user1, &1, &uri(&env));
+
+    client.transfer(&user1, &user2, &1);
+    assert_eq!(client.owner_of(&1), user2);
+    assert_eq!(client.balance(&user1), 0);
+    assert_eq!(client.balance(&user2), 1);
+}
+
+#[test]
+fn transfer_from_approved() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+
+    client.approve(&owner, &spender, &1);
+    client.transfer_from(&spender, &owner, &spender, &1);
+    assert_eq!(client.owner_of(&1), spender);
+}
+
+#[test]
+fn transfer_from_operator() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let to = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+    client.mint(&owner, &2, &uri(&env));
+
+    client.approve_all(&owner, &operator, &true);
+    client.transfer_from(&operator, &owner, &to, &1);
+    client.transfer_from(&operator, &owner, &to, &2);
+    assert_eq!(client.balance(&to), 2);
+}
+
+#[test]
+fn burn() {
+    let env = Env::default();
+    let client = nft(&env);
+    let user = Address::generate(&env);
+    client.mint(&user, &1, &uri(&env));
+
+    client.burn(&user, &1);
+    assert_eq!(client.balance(&user), 0);
+}
+
+#[test]
+fn using_an_operator_extends_its_ttl() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+    client.mint(&owner, &2, &uri(&env));
+    client.approve_all(&owner, &operator, &true);
+
+    let key: Vec<Val> = (Symbol::new(&env, "NftOperator"), &owner, &operator).into_val(&env);
+    let ttl = || env.as_contract(&client.address, || env.storage().persistent().get_ttl(&key));
+    let extended = ttl();
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number += 2 * DAY_IN_LEDGERS);
+    assert_eq!(ttl(), extended - 2 * DAY_IN_LEDGERS);
+    client.transfer_from(&operator, &owner, &operator, &1);
+    assert_eq!(ttl(), extended);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn burned_token_has_no_owner() {
+    let env = Env::default();
+    let client = nft(&env);
+    let user = Address::generate(&env);
+    client.mint(&user, &1, &uri(&env));
+    client.burn(&user, &1);
+    as_nft(&env, &client, |token| token.owner_of(1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn transfer_by_non_owner() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+    as_nft(&env, &client, |token| {
+        token.transfer(other.clone(), other, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn approve_by_non_owner() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let other = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+    as_nft(&env, &client, |token| {
+        token.approve(other.clone(), other, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn transfer_from_not_approved() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+    as_nft(&env, &client, |token| {
+        token.transfer_from(spender.clone(), owner, spender, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn transfer_clears_approval() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+    client.approve(&owner, &spender, &1);
+    client.transfer(&owner, &owner, &1);
+    as_nft(&env, &client, |token| {
+        token.transfer_from(spender.clone(), owner, spender, 1);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn mint_existing_token() {
+    let env = Env::default();
+    let client = nft(&env);
+    let user = Address::generate(&env);
+    client.mint(&user, &1, &uri(&env));
+    as_nft(&env, &client, |token| token.mint(user, 1, uri(&env)));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn mint_without_core_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client =
+        SorobanContract__Client::new(&env, &env.register_contract(None, SorobanContract__));
+    let user = Address::generate(&env);
+    as_nft(&env, &client, |token| token.mint(user, 1, uri(&env)));
+}
+
+/// The auths recorded for a call of `function` that only `address` authorized
+fn authorized_by(
+    client: &SorobanContract__Client,
+    address: &Address,
+    function: &str,
+    args: Vec<Val>,
+) -> std::vec::Vec<(Address, AuthorizedInvocation)> {
+    std::vec![(
+        address.clone(),
+        AuthorizedInvocation {
+            function: AuthorizedFunction::Contract((
+                client.address.clone(),
+                Symbol::new(&client.env, function),
+                args,
+            )),
+            sub_invocations: std::vec![],
+        },
+    )]
+}
+
+#[test]
+fn moving_tokens_requires_auth() {
+    let env = Env::default();
+    let client = nft(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&owner, &1, &uri(&env));
+    client.mint(&owner, &2, &uri(&env));
+
+    client.transfer(&owner, &spender, &1);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &owner,
+            "transfer",
+            (&owner, &spender, 1_u32).into_val(&env)
+        )
+    );
+    client.approve(&owner, &spender, &2);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &owner,
+            "approve",
+            (&owner, &spender, 2_u32).into_val(&env)
+        )
+    );
+    client.transfer_from(&spender, &owner, &spender, &2);
+    assert_eq!(
+        env.auths(),
+        authorized_by(
+            &client,
+            &spender,
+            "transfer_from",
+            (&spender, &owner, &spender, 2_u32).into_val(&env)
+        )
+    );
+    client.burn(&spender, &2);
+    assert_eq!(
+        env.auths(),
+        authorized_by(&client, &spender, "burn", (&spender, 2_u32).into_val(&env))
+    );
+}
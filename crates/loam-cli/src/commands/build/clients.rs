@@ -1,34 +1,40 @@
 #![allow(clippy::struct_excessive_bools)]
-use crate::commands::build::env_toml;
+use crate::commands::build::{env_toml, init_script, sandbox, spoon};
+use cargo_metadata::{DependencyKind, Package};
+use loam_build::deps::{DepKind, PackageExt};
 use serde_json;
 use soroban_cli::commands::NetworkRunnable;
 use soroban_cli::utils::contract_hash;
 use soroban_cli::{commands as cli, CommandParser};
-use std::collections::BTreeMap as Map;
-use std::fmt::Debug;
-use std::hash::Hash;
-use stellar_xdr::curr::Error as xdrError;
+use std::fmt::{Debug, Write};
+use stellar_xdr::curr::{
+    self as xdr, ContractDataDurability, Error as xdrError, LedgerKey, LedgerKeyContractCode,
+    LedgerKeyContractData, ScAddress, ScVal,
+};
 
 use super::env_toml::Network;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, clap::ValueEnum)]
-pub enum LoamEnv {
-    Development,
-    Testing,
-    Staging,
-    Production,
-}
+/// `LOAM_ENV` for commands other than `loam dev`, unless set
+pub const DEFAULT_ENV: &str = "production";
 
-impl std::fmt::Display for LoamEnv {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", format!("{self:?}").to_lowercase())
-    }
-}
+/// Client written for each contract unless the environment sets `clients.template`
+const DEFAULT_TEMPLATE: &str = r"import * as Client from '{{name}}';
+import { rpcUrl } from './util';
 
-#[derive(clap::Args, Debug, Clone, Copy)]
+export default new Client.Client({
+  networkPassphrase: '{{network_passphrase}}',
+  contractId: '{{contract_id}}',
+  rpcUrl,
+  allowHttp: {{allow_http}},
+  publicKey: undefined,
+});
+";
+
+#[derive(clap::Args, Debug, Clone)]
 pub struct Args {
-    #[arg(env = "LOAM_ENV", value_enum)]
-    pub env: Option<LoamEnv>,
+    /// Environment in environments.toml to use
+    #[arg(env = "LOAM_ENV")]
+    pub env: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -47,7 +53,7 @@ pub enum Error {
     NeedAtLeastOneAccount,
     #[error("⛔ ️No contract named {0:?}")]
     BadContractName(String),
-    #[error("⛔ ️Contract update not allowed in production for {0:?}")]
+    #[error("⛔ ️Contract update not allowed in this environment for {0:?}; set `allow-updates = true` to allow it")]
     ContractUpdateNotAllowed(String),
     #[error(transparent)]
     ContractInstall(#[from] cli::contract::install::Error),
@@ -69,52 +75,132 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+    #[error("⛔ ️invalid contract ID {0:?}")]
+    InvalidContractId(String),
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+    #[error(transparent)]
+    Rpc(#[from] soroban_rpc::Error),
+    #[error(transparent)]
+    ContractExtend(#[from] cli::contract::extend::Error),
+    #[error(transparent)]
+    Spoon(#[from] spoon::Error),
+    #[error(transparent)]
+    Sandbox(#[from] sandbox::Error),
+    #[error(transparent)]
+    InitScript(#[from] init_script::Error),
+    #[error("⛔ ️contract {name:?} depends on {dependency:?}, which is not a contract in this workspace or environment")]
+    UnknownDependency { name: String, dependency: String },
+    #[error("⛔ ️contracts depend on each other in a cycle: {0:?}")]
+    DependencyCycle(Vec<String>),
+    #[error("⛔ ️reading client template {path:?}: {error}")]
+    TemplateNotFound {
+        path: std::path::PathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("⛔ ️Spooning contracts is not allowed in this environment, for {0:?}; set `allow-updates = true` to allow it")]
+    SpoonNotAllowed(String),
+    #[error("⛔ ️not spooning {0:?} into a network that isn't local; set `allow-live-spoon = true` to allow it")]
+    LiveSpoonNotAllowed(String),
+    #[error(transparent)]
+    KeyAddress(#[from] cli::keys::address::Error),
+    #[error(transparent)]
+    Fund(#[from] cli::keys::fund::Error),
+    #[error(transparent)]
+    Secret(#[from] cli::config::secret::Error),
+    #[error("⛔ ️account {name:?} imports its keys from ${var}, which is not set")]
+    MissingSecretEnv { name: String, var: String },
+    #[error("⛔ ️no keys for account {0:?}, and Loam does not generate keys on mainnet; set `secret-env` for it or add them with `stellar keys add`")]
+    NoKeysOnMainnet(String),
+    #[error("⛔ ️account {0:?} does not exist on mainnet; create and fund it first")]
+    MainnetAccountNotFound(String),
+    #[error("⛔ ️account {name:?} holds {balance} stroops on mainnet, less than its minimum balance of {min_balance}")]
+    MainnetAccountUnderfunded {
+        name: String,
+        balance: i64,
+        min_balance: i64,
+    },
+    #[error("⛔ ️contract {name:?} not found at {id}")]
+    ContractNotFound { name: String, id: String },
+    #[error("⛔ ️the `networks` export of the bindings generated in {0:?} does not end with `as const`, so the networks of other environments cannot be added to it")]
+    MalformedNetworksExport(std::path::PathBuf),
 }
 
+const MAINNET_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+
+/// Balance accounts need on mainnet unless they set `min-balance`: 1 XLM, in stroops
+const MAINNET_MIN_BALANCE: i64 = 10_000_000;
+
 impl Args {
     pub async fn run(
         &self,
         workspace_root: &std::path::Path,
-        package_names: Vec<String>,
+        packages: &[Package],
     ) -> Result<(), Error> {
         let Some(current_env) =
-            env_toml::Environment::get(workspace_root, &self.loam_env(LoamEnv::Production))?
+            env_toml::Environment::get(workspace_root, &self.loam_env(DEFAULT_ENV))?
         else {
             return Ok(());
         };
 
-        Self::add_network_to_env(&current_env.network)?;
-        Self::handle_accounts(current_env.accounts.as_deref()).await?;
+        let network = Self::add_network_to_env(workspace_root, &current_env.network)?;
+        Self::handle_accounts(current_env.accounts.as_deref(), &network).await?;
+        self.handle_spooned_contracts(workspace_root, &current_env, &network)
+            .await?;
+        self.handle_existing_contracts(workspace_root, &current_env)
+            .await?;
+        let package_names = deploy_order(packages, &current_env)?;
         self.handle_contracts(
             workspace_root,
-            current_env.contracts.as_ref(),
+            &current_env,
+            &network.rpc_url,
             package_names,
-            &current_env.network,
         )
         .await?;
+        if current_env.clients.index {
+            Self::write_clients_index(workspace_root, &current_env, packages)?;
+        }
 
         Ok(())
     }
 
-    fn loam_env(self, default: LoamEnv) -> String {
-        self.env.unwrap_or(default).to_string().to_lowercase()
+    pub(crate) fn loam_env(&self, default: &str) -> String {
+        self.env.clone().unwrap_or_else(|| default.to_string())
     }
 
     /// Parse the network settings from the environments.toml file and set `STELLAR_RPC_URL` and
-    /// `STELLAR_NETWORK_PASSPHRASE`.
+    /// `STELLAR_NETWORK_PASSPHRASE`, returning the RPC URL and passphrase they were set to.
     ///
     /// We could set `STELLAR_NETWORK` instead, but when importing contracts, we want to hard-code
     /// the network passphrase. So if given a network name, we use soroban-cli to fetch the RPC url
-    /// & passphrase for that named network, and still set the environment variables.
-    fn add_network_to_env(network: &env_toml::Network) -> Result<(), Error> {
-        match &network {
+    /// & passphrase for that named network, and still set the environment variables. A network
+    /// set to `run-locally` is started in-process first.
+    pub(crate) fn add_network_to_env(
+        workspace_root: &std::path::Path,
+        network: &env_toml::Network,
+    ) -> Result<cli::network::Network, Error> {
+        let resolved = match &network {
+            Network {
+                run_locally: true,
+                rpc_url,
+                network_passphrase,
+                ..
+            } => {
+                let rpc_url = rpc_url.as_deref().unwrap_or(sandbox::DEFAULT_RPC_URL);
+                let passphrase = network_passphrase
+                    .as_deref()
+                    .unwrap_or(cli::network::LOCAL_NETWORK_PASSPHRASE);
+                sandbox::start(workspace_root, rpc_url, passphrase)?;
+                cli::network::Network {
+                    rpc_url: rpc_url.to_string(),
+                    network_passphrase: passphrase.to_string(),
+                }
+            }
             Network {
                 name: Some(name), ..
             } => {
-                let cli::network::Network {
-                    rpc_url,
-                    network_passphrase,
-                } = (cli::network::Args {
+                let resolved = (cli::network::Args {
                     network: Some(name.clone()),
                     rpc_url: None,
                     network_passphrase: None,
@@ -124,33 +210,43 @@ impl Args {
                     config_dir: None,
                 })?;
                 eprintln!("🌐 using {name} network");
-                std::env::set_var("STELLAR_RPC_URL", rpc_url);
-                std::env::set_var("STELLAR_NETWORK_PASSPHRASE", network_passphrase);
+                resolved
             }
             Network {
                 rpc_url: Some(rpc_url),
                 network_passphrase: Some(passphrase),
                 ..
             } => {
-                std::env::set_var("STELLAR_RPC_URL", rpc_url);
-                std::env::set_var("STELLAR_NETWORK_PASSPHRASE", passphrase);
                 eprintln!("🌐 using network at {rpc_url}");
+                cli::network::Network {
+                    rpc_url: rpc_url.clone(),
+                    network_passphrase: passphrase.clone(),
+                }
             }
             _ => return Err(Error::MalformedNetwork),
-        }
-
-        Ok(())
+        };
+        std::env::set_var("STELLAR_RPC_URL", &resolved.rpc_url);
+        std::env::set_var("STELLAR_NETWORK_PASSPHRASE", &resolved.network_passphrase);
+        Ok(resolved)
     }
 
+    /// Network arguments for soroban-cli commands built without parsing, falling back to the
+    /// environment variables set by `add_network_to_env`
     fn get_network_args(network: &Network) -> cli::network::Args {
         cli::network::Args {
-            rpc_url: network.rpc_url.clone(),
-            network_passphrase: network.network_passphrase.clone(),
+            rpc_url: network
+                .rpc_url
+                .clone()
+                .or_else(|| std::env::var("STELLAR_RPC_URL").ok()),
+            network_passphrase: network
+                .network_passphrase
+                .clone()
+                .or_else(|| std::env::var("STELLAR_NETWORK_PASSPHRASE").ok()),
             network: network.name.clone(),
         }
     }
 
-    fn get_config_locator() -> cli::config::locator::Args {
+    pub(crate) fn get_config_locator() -> cli::config::locator::Args {
         cli::config::locator::Args {
             global: false,
             config_dir: None,
@@ -164,12 +260,13 @@ impl Args {
         config_dir.get_contract_id(name, &network_passphrase)
     }
 
-    async fn contract_hash_matches(
+    /// Hash of the Wasm the contract at `contract_id` runs, or `None` if it isn't on the network,
+    /// such as after the network was reset
+    async fn deployed_hash(
         &self,
         contract_id: &str,
-        hash: &str,
         network: &Network,
-    ) -> Result<bool, Error> {
+    ) -> Result<Option<String>, Error> {
         let result = cli::contract::fetch::Cmd {
             contract_id: contract_id.to_string(),
             out_file: None,
@@ -182,11 +279,11 @@ impl Args {
         match result {
             Ok(result) => {
                 let ctrct_hash = contract_hash(&result)?;
-                Ok(hex::encode(ctrct_hash) == hash)
+                Ok(Some(hex::encode(ctrct_hash)))
             }
             Err(e) => {
                 if e.to_string().contains("Contract not found") {
-                    Ok(false)
+                    Ok(None)
                 } else {
                     Err(Error::ContractFetch(e))
                 }
@@ -194,58 +291,101 @@ impl Args {
         }
     }
 
-    fn save_contract_alias(
+    pub(crate) fn save_contract_alias(
         name: &str,
         contract_id: &str,
         network: &Network,
     ) -> Result<(), cli::config::locator::Error> {
         let config_dir = Self::get_config_locator();
+        // Named networks only have their passphrase set in the environment by `add_network_to_env`
         let passphrase = network
             .network_passphrase
             .clone()
+            .or_else(|| std::env::var("STELLAR_NETWORK_PASSPHRASE").ok())
             .expect("You must set a network passphrase.");
         config_dir.save_contract_id(&passphrase, contract_id, name)
     }
 
+    /// Write the client the frontend imports a contract with, from `clients.template` or
+    /// [`DEFAULT_TEMPLATE`]
     fn write_contract_template(
-        self,
+        &self,
         workspace_root: &std::path::Path,
+        current_env: &env_toml::Environment,
         name: &str,
         contract_id: &str,
     ) -> Result<(), Error> {
-        let allow_http = if self.loam_env(LoamEnv::Production) == "development" {
-            "\n  allowHttp: true,"
-        } else {
-            ""
+        let clients = &current_env.clients;
+        let template = match &clients.template {
+            Some(path) => std::fs::read_to_string(workspace_root.join(path)).map_err(|error| {
+                Error::TemplateNotFound {
+                    path: path.clone(),
+                    error,
+                }
+            })?,
+            None => DEFAULT_TEMPLATE.to_string(),
         };
-        let network = std::env::var("STELLAR_NETWORK_PASSPHRASE")
+        let network_passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
             .expect("No STELLAR_NETWORK_PASSPHRASE environment variable set");
-        let template = format!(
-            r#"import * as Client from '{name}';
-import {{ rpcUrl }} from './util';
-    
-export default new Client.Client({{
-  networkPassphrase: '{network}',
-  contractId: '{contract_id}',
-  rpcUrl,{allow_http}
-  publicKey: undefined,
-}});
-"#
-        );
-        let path = workspace_root.join(format!("src/contracts/{name}.ts"));
-        std::fs::write(path, template)?;
+        let rpc_url = std::env::var("STELLAR_RPC_URL").unwrap_or_default();
+        let env = self.loam_env(DEFAULT_ENV);
+        let allow_http = current_env.allow_http.unwrap_or_default().to_string();
+        let client = render(&template, |variable| match variable {
+            "name" => Some(name.to_string()),
+            "contract_id" => Some(contract_id.to_string()),
+            "network_passphrase" => Some(network_passphrase.clone()),
+            "rpc_url" => Some(rpc_url.clone()),
+            "env" => Some(env.clone()),
+            "allow_http" => Some(allow_http.clone()),
+            // Contracts are deployed after their dependencies, so these have IDs by now
+            _ => Self::get_contract_alias(env_toml::contract_reference(variable)?)
+                .ok()
+                .flatten(),
+        });
+        let out_dir = workspace_root.join(&clients.out_dir);
+        std::fs::create_dir_all(&out_dir)?;
+        let path = out_dir.join(format!("{name}.{}", clients.extension()));
+        std::fs::write(path, client)?;
         Ok(())
     }
 
-    async fn account_exists(account_name: &str) -> Result<bool, Error> {
-        // TODO: this is a workaround until generate is changed to not overwrite accounts
-        Ok(cli::keys::fund::Cmd::parse_arg_vec(&[account_name])?
-            .run()
-            .await
-            .is_ok())
+    /// Write `index` to the clients' directory, re-exporting the client of each contract that has
+    /// one there
+    fn write_clients_index(
+        workspace_root: &std::path::Path,
+        current_env: &env_toml::Environment,
+        packages: &[Package],
+    ) -> Result<(), Error> {
+        let clients = &current_env.clients;
+        let out_dir = workspace_root.join(&clients.out_dir);
+        let extension = clients.extension();
+        let contracts = packages
+            .iter()
+            .map(|package| package.name.replace('-', "_"))
+            .chain(
+                current_env
+                    .contracts
+                    .iter()
+                    .flatten()
+                    .map(|(c, _)| c.to_string()),
+            )
+            .collect::<std::collections::BTreeSet<_>>();
+        let index = contracts
+            .iter()
+            .filter(|name| out_dir.join(format!("{name}.{extension}")).exists())
+            .fold(String::new(), |mut index, name| {
+                let _ = writeln!(index, "export {{ default as {name} }} from './{name}';");
+                index
+            });
+        std::fs::create_dir_all(&out_dir)?;
+        std::fs::write(out_dir.join(format!("index.{extension}")), index)?;
+        Ok(())
     }
 
-    async fn handle_accounts(accounts: Option<&[env_toml::Account]>) -> Result<(), Error> {
+    pub(crate) async fn handle_accounts(
+        accounts: Option<&[env_toml::Account]>,
+        network: &cli::network::Network,
+    ) -> Result<(), Error> {
         let Some(accounts) = accounts else {
             return Err(Error::NeedAtLeastOneAccount);
         };
@@ -263,15 +403,20 @@ export default new Client.Client({{
             _ => return Err(Error::OnlyOneDefaultAccount(default_account_candidates)),
         };
 
+        let mainnet = network.network_passphrase == MAINNET_PASSPHRASE;
+        // Only mainnet accounts are looked up, as friendbot leaves existing accounts alone
+        let client = if mainnet {
+            Some(soroban_rpc::Client::new(&network.rpc_url)?)
+        } else {
+            None
+        };
         for account in accounts {
-            if Self::account_exists(&account.name).await? {
-                eprintln!(
-                    "ℹ️ account {:?} already exists, skipping key creation",
-                    account.name
-                );
-            } else {
-                eprintln!("🔐 creating keys for {:?}", account.name);
-                cli::keys::generate::Cmd::parse_arg_vec(&[&account.name])?
+            Self::handle_keys(account, mainnet).await?;
+            if let Some(client) = &client {
+                Self::check_mainnet_account(client, account).await?;
+            } else if account.fund {
+                eprintln!("💰 funding account {:?}", account.name);
+                cli::keys::fund::Cmd::parse_arg_vec(&[&account.name])?
                     .run()
                     .await?;
             }
@@ -282,19 +427,77 @@ export default new Client.Client({{
         Ok(())
     }
 
+    /// Check that `account` exists on mainnet and holds at least its minimum balance
+    async fn check_mainnet_account(
+        client: &soroban_rpc::Client,
+        account: &env_toml::Account,
+    ) -> Result<(), Error> {
+        let address = cli::keys::address::Cmd::parse_arg_vec(&[&account.name])?
+            .public_key()?
+            .to_string();
+        let entry = match client.get_account(&address).await {
+            Ok(entry) => entry,
+            Err(soroban_rpc::Error::NotFound(..)) => {
+                return Err(Error::MainnetAccountNotFound(account.name.clone()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let min_balance = account.min_balance.unwrap_or(MAINNET_MIN_BALANCE);
+        if entry.balance < min_balance {
+            return Err(Error::MainnetAccountUnderfunded {
+                name: account.name.clone(),
+                balance: entry.balance,
+                min_balance,
+            });
+        }
+        Ok(())
+    }
+
+    /// Make sure there are keys for `account`, importing them from its `secret-env` or else
+    /// generating them. Keys are never generated on mainnet, where they would need funding by hand.
+    async fn handle_keys(account: &env_toml::Account, mainnet: bool) -> Result<(), Error> {
+        let locator = Self::get_config_locator();
+        if locator.read_identity(&account.name).is_ok() {
+            eprintln!(
+                "ℹ️ account {:?} already exists, skipping key creation",
+                account.name
+            );
+        } else if let Some(var) = &account.secret_env {
+            let secret = std::env::var(var).map_err(|_| Error::MissingSecretEnv {
+                name: account.name.clone(),
+                var: var.clone(),
+            })?;
+            eprintln!("🔑 importing keys for {:?} from ${var}", account.name);
+            locator.write_identity(&account.name, &secret.parse()?)?;
+        } else if mainnet {
+            return Err(Error::NoKeysOnMainnet(account.name.clone()));
+        } else {
+            eprintln!("🔐 creating keys for {:?}", account.name);
+            cli::keys::generate::Cmd::parse_arg_vec(&[&account.name, "--no-fund"])?
+                .run()
+                .await?;
+        }
+        Ok(())
+    }
+
     async fn handle_contracts(
         &self,
         workspace_root: &std::path::Path,
-        contracts: Option<&Map<Box<str>, env_toml::Contract>>,
+        current_env: &env_toml::Environment,
+        rpc_url: &str,
         package_names: Vec<String>,
-        network: &Network,
     ) -> Result<(), Error> {
         if package_names.is_empty() {
             return Ok(());
         }
+        let contracts = current_env.contracts.as_ref();
+        let network = &current_env.network;
         // ensure contract names are valid
         if let Some(contracts) = contracts {
-            for (name, _) in contracts.iter().filter(|(_, settings)| settings.client) {
+            for (name, _) in contracts
+                .iter()
+                .filter(|(_, settings)| settings.client && settings.id.is_none())
+            {
                 let wasm_path = workspace_root.join(format!("target/loam/{name}.wasm"));
                 if !wasm_path.exists() {
                     return Err(Error::BadContractName(name.to_string()));
@@ -306,9 +509,10 @@ export default new Client.Client({{
                 Some(contracts) => contracts.get(&name as &str),
                 None => None,
             };
-            // Skip only if contract is found and its `client` setting is false
+            // Skip only if contract is found and its `client` setting is false, or it is already
+            // deployed with an `id`
             if let Some(c) = settings {
-                if !c.client {
+                if !c.client || c.id.is_some() {
                     continue;
                 }
             }
@@ -316,129 +520,510 @@ export default new Client.Client({{
             if !wasm_path.exists() {
                 return Err(Error::BadContractName(name.to_string()));
             }
-            eprintln!("📲 installing {name:?} wasm bytecode on-chain...");
-            let hash = cli::contract::install::Cmd::parse_arg_vec(&[
-                "--wasm",
-                wasm_path
-                    .to_str()
-                    .expect("we do not support non-utf8 paths"),
-            ])?
-            .run_against_rpc_server(None, None)
-            .await?
-            .into_result()
-            .expect("no hash returned by 'contract install'")
-            .to_string();
-            eprintln!("    ↳ hash: {hash}");
+            let options = deploy_options(settings);
+            let hash = Self::install_wasm(&name, &wasm_path, &options).await?;
 
-            // Check if we have an alias saved for this contract
+            // Check if we have an alias saved for this contract, and it is still on the network
             let alias = Self::get_contract_alias(&name)?;
-            if let Some(contract_id) = alias {
-                match self
-                    .contract_hash_matches(&contract_id, &hash, network)
-                    .await
-                {
-                    Ok(true) => {
-                        eprintln!("✅ Contract {name:?} is up to date");
-                        continue;
-                    }
-                    Ok(false) if self.loam_env(LoamEnv::Production) == "production" => {
-                        return Err(Error::ContractUpdateNotAllowed(name.to_string()));
+            let deployed = match &alias {
+                Some(contract_id) => self.deployed_hash(contract_id, network).await?,
+                None => None,
+            };
+            if let (Some(contract_id), Some(deployed)) = (&alias, &deployed) {
+                if *deployed == hash {
+                    eprintln!("✅ Contract {name:?} is up to date");
+                    Self::extend_ttls(rpc_url, &name, contract_id, &hash, &current_env.ttl).await?;
+                    if let Some(init_script) = settings.and_then(|s| s.init.as_ref()) {
+                        if current_env.run_init.unwrap_or_default() {
+                            self.run_init_script(
+                                workspace_root,
+                                &name,
+                                contract_id,
+                                init_script,
+                                false,
+                            )
+                            .await?;
+                        }
                     }
-                    Ok(false) => eprintln!("🔄 Updating contract {name:?}"),
-                    Err(e) => return Err(e),
+                    continue;
+                }
+                if !current_env.allow_updates.unwrap_or_default() {
+                    return Err(Error::ContractUpdateNotAllowed(name.to_string()));
                 }
+                eprintln!("🔄 Updating contract {name:?}");
             }
 
             eprintln!("🪞 instantiating {name:?} smart contract");
-            let contract_id = cli::contract::deploy::wasm::Cmd::parse_arg_vec(&[
-                "--alias",
-                &name,
-                "--wasm-hash",
-                &hash,
-            ])?
-            .run_against_rpc_server(None, None)
-            .await?
-            .into_result()
-            .expect("no contract id returned by 'contract deploy'");
+            let mut deploy_args = vec!["--alias", &name, "--wasm-hash", &hash];
+            deploy_args.extend(options.iter().map(String::as_str));
+            // An update needs a new ID, and the salt would give it the one already taken
+            if let (Some(salt), None) = (settings.and_then(|c| c.salt.as_ref()), &deployed) {
+                deploy_args.extend(["--salt", salt]);
+            }
+            let contract_id = cli::contract::deploy::wasm::Cmd::parse_arg_vec(&deploy_args)?
+                .run_against_rpc_server(None, None)
+                .await?
+                .into_result()
+                .expect("no contract id returned by 'contract deploy'");
             eprintln!("    ↳ contract_id: {contract_id}");
 
             // Save the alias for future use
             Self::save_contract_alias(&name, &contract_id, network)?;
 
-            // Run init script if we're in development or test environment
-            if self.loam_env(LoamEnv::Production) == "development"
-                || self.loam_env(LoamEnv::Production) == "testing"
-            {
-                if let Some(settings) = settings {
-                    if let Some(init_script) = &settings.init {
-                        eprintln!("🚀 Running initialization script for {name:?}");
-                        self.run_init_script(&name, &contract_id, init_script)
-                            .await?;
-                    }
+            if let Some(init_script) = settings.and_then(|s| s.init.as_ref()) {
+                if current_env.run_init.unwrap_or_default() {
+                    self.run_init_script(workspace_root, &name, &contract_id, init_script, true)
+                        .await?;
                 }
             }
 
-            eprintln!("🎭 binding {name:?} contract");
-            cli::contract::bindings::typescript::Cmd::parse_arg_vec(&[
-                "--contract-id",
-                &contract_id,
-                "--output-dir",
-                workspace_root
-                    .join(format!("packages/{name}"))
-                    .to_str()
-                    .expect("we do not support non-utf8 paths"),
-                "--overwrite",
-            ])?
-            .run()
-            .await?;
-
-            eprintln!("🍽️ importing {:?} contract", name.clone());
-            self.write_contract_template(workspace_root, &name, &contract_id)?;
+            self.bind_and_import(workspace_root, current_env, &name, &contract_id)
+                .await?;
         }
 
         Ok(())
     }
 
-    async fn run_init_script(
+    /// Install a contract's Wasm, with extra `contract install` arguments, returning its hash
+    async fn install_wasm(
+        name: &str,
+        wasm_path: &std::path::Path,
+        options: &[String],
+    ) -> Result<String, Error> {
+        eprintln!("📲 installing {name:?} wasm bytecode on-chain...");
+        let mut args = vec![
+            "--wasm",
+            wasm_path
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+        ];
+        args.extend(options.iter().map(String::as_str));
+        let hash = cli::contract::install::Cmd::parse_arg_vec(&args)?
+            .run_against_rpc_server(None, None)
+            .await?
+            .into_result()
+            .expect("no hash returned by 'contract install'")
+            .to_string();
+        eprintln!("    ↳ hash: {hash}");
+        Ok(hash)
+    }
+
+    async fn bind_and_import(
         &self,
+        workspace_root: &std::path::Path,
+        current_env: &env_toml::Environment,
+        name: &str,
+        contract_id: &str,
+    ) -> Result<(), Error> {
+        eprintln!("🎭 binding {name:?} contract");
+        cli::contract::bindings::typescript::Cmd::parse_arg_vec(&[
+            "--contract-id",
+            contract_id,
+            "--output-dir",
+            workspace_root
+                .join(format!("packages/{name}"))
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+            "--overwrite",
+        ])?
+        .run()
+        .await?;
+        self.merge_networks(workspace_root, name, contract_id)?;
+
+        eprintln!("🍽️ importing {name:?} contract");
+        self.write_contract_template(workspace_root, current_env, name, contract_id)?;
+        Ok(())
+    }
+
+    /// Replace the `networks` export of the generated `packages/{name}`, which only has the current
+    /// network, with the contract's ID and network passphrase in every environment it has an alias
+    /// for, keyed by environment name. Other environments on the current network, or sharing one
+    /// with each other, are left out, as their aliases would be the same.
+    fn merge_networks(
+        &self,
+        workspace_root: &std::path::Path,
         name: &str,
         contract_id: &str,
-        init_script: &str,
     ) -> Result<(), Error> {
-        for line in init_script.lines() {
-            let line = line.trim();
-            if line.is_empty() {
+        let current_env = self.loam_env(DEFAULT_ENV);
+        let passphrases = env_toml::Environment::get_all(workspace_root)?
+            .into_iter()
+            .filter_map(|(env, settings)| Some((env, settings.network.passphrase()?)))
+            .collect::<Vec<_>>();
+        let mut networks = serde_json::Map::new();
+        for (env, passphrase) in &passphrases {
+            let contract_id = if **env == *current_env {
+                contract_id.to_string()
+            } else if passphrases
+                .iter()
+                .any(|(other, p)| other != env && p == passphrase)
+            {
+                // Aliases are saved per network, so they can't tell these environments apart
+                eprintln!(
+                    "⚠️ Leaving {env:?} out of {name:?}'s networks, as it shares its network with \
+                     another environment"
+                );
                 continue;
+            } else if let Some(contract_id) =
+                Self::get_config_locator().get_contract_id(name, passphrase)?
+            {
+                contract_id
+            } else {
+                continue;
+            };
+            networks.insert(
+                env.to_string(),
+                serde_json::json!({ "networkPassphrase": passphrase, "contractId": contract_id }),
+            );
+        }
+
+        let path = workspace_root.join(format!("packages/{name}/src/index.ts"));
+        let index = std::fs::read_to_string(&path)?;
+        let Some(start) = index.find("export const networks = ") else {
+            return Ok(());
+        };
+        let Some(length) = index[start..].find(" as const") else {
+            return Err(Error::MalformedNetworksExport(path));
+        };
+        let end = start + length;
+        let networks = serde_json::to_string_pretty(&networks)?;
+        std::fs::write(
+            path,
+            format!(
+                "{}export const networks = {networks}{}",
+                &index[..start],
+                &index[end..]
+            ),
+        )?;
+        Ok(())
+    }
+
+    /// Check that contracts which set an `id` exist, warning if their Wasm differs from the local
+    /// build, then bind and import them without deploying anything
+    async fn handle_existing_contracts(
+        &self,
+        workspace_root: &std::path::Path,
+        current_env: &env_toml::Environment,
+    ) -> Result<(), Error> {
+        let network = &current_env.network;
+        let existing = current_env
+            .contracts
+            .iter()
+            .flatten()
+            .filter_map(|(name, settings)| Some((name, settings, settings.id.as_ref()?)));
+        for (name, settings, id) in existing {
+            let passphrase = std::env::var("STELLAR_NETWORK_PASSPHRASE")
+                .expect("No STELLAR_NETWORK_PASSPHRASE environment variable set");
+            let contract_id = Self::get_config_locator()
+                .get_contract_id(id, &passphrase)?
+                .unwrap_or_else(|| id.clone());
+            soroban_cli::utils::contract_id_from_str(&contract_id)
+                .map_err(|_| Error::InvalidContractId(contract_id.clone()))?;
+
+            eprintln!("🔎 checking {name:?} contract at {contract_id}");
+            let wasm = cli::contract::fetch::Cmd {
+                contract_id: contract_id.clone(),
+                out_file: None,
+                locator: Self::get_config_locator(),
+                network: Self::get_network_args(network),
             }
+            .run_against_rpc_server(None, None)
+            .await;
+            let wasm = match wasm {
+                Ok(wasm) => wasm,
+                Err(e) if e.to_string().contains("Contract not found") => {
+                    return Err(Error::ContractNotFound {
+                        name: name.to_string(),
+                        id: contract_id,
+                    })
+                }
+                Err(e) => return Err(Error::ContractFetch(e)),
+            };
+            let hash = hex::encode(contract_hash(&wasm)?);
+            let wasm_path = workspace_root.join(format!("target/loam/{name}.wasm"));
+            match std::fs::read(&wasm_path) {
+                Ok(local) if hex::encode(contract_hash(&local)?) != hash => eprintln!(
+                    "⚠️ Contract {name:?} runs Wasm {hash}, which differs from the local build"
+                ),
+                Ok(_) => eprintln!("✅ Contract {name:?} matches the local build"),
+                Err(_) => eprintln!("    ↳ hash: {hash}"),
+            }
+            Self::save_contract_alias(name, &contract_id, network)?;
 
-            let mut args = vec!["--id", contract_id];
-            let mut source_account: Option<&str> = None;
+            if settings.client {
+                self.bind_and_import(workspace_root, current_env, name, &contract_id)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Recreate contracts that set an `address` with the Wasm and storage they have in another
+    /// environment or ledger snapshot, then bind and import them like workspace contracts
+    async fn handle_spooned_contracts(
+        &self,
+        workspace_root: &std::path::Path,
+        current_env: &env_toml::Environment,
+        resolved: &cli::network::Network,
+    ) -> Result<(), Error> {
+        let network = &current_env.network;
+        let spooned = current_env
+            .contracts
+            .iter()
+            .flatten()
+            .filter_map(|(name, settings)| Some((name, settings, settings.address.as_ref()?)));
+        for (name, settings, address) in spooned {
+            if !current_env.allow_updates.unwrap_or_default() {
+                return Err(Error::SpoonNotAllowed(name.to_string()));
+            }
+            if !spoon::is_local(resolved) && !current_env.allow_live_spoon.unwrap_or_default() {
+                return Err(Error::LiveSpoonNotAllowed(name.to_string()));
+            }
+            let spooned = spoon::fetch(workspace_root, name, address, settings).await?;
+            let wasm_path = workspace_root.join(format!("target/loam/{name}.wasm"));
+            std::fs::create_dir_all(workspace_root.join("target/loam"))?;
+            std::fs::write(&wasm_path, &spooned.wasm)?;
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            let mut command_parts = vec!["--"];
+            let hash = Self::install_wasm(name, &wasm_path, &[]).await?;
 
-            for part in &parts {
-                if let Some(value) = part.strip_prefix("STELLAR_ACCOUNT=") {
-                    source_account = Some(value);
-                } else {
-                    command_parts.push(*part);
+            if let Some(contract_id) = Self::get_contract_alias(name)? {
+                if self.deployed_hash(&contract_id, network).await? == Some(hash.clone()) {
+                    eprintln!("✅ Contract {name:?} is up to date");
+                    continue;
                 }
             }
 
-            if let Some(account) = source_account {
+            let contract_id =
+                spoon::recreate(workspace_root, name, &spooned.entries, &hash).await?;
+            eprintln!("    ↳ contract_id: {contract_id}");
+            Self::save_contract_alias(name, &contract_id, network)?;
+
+            if settings.client {
+                self.bind_and_import(workspace_root, current_env, name, &contract_id)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Extend the TTL of a contract's instance and Wasm entries that have fewer than
+    /// `ttl.threshold` ledgers left to live
+    async fn extend_ttls(
+        rpc_url: &str,
+        name: &str,
+        contract_id: &str,
+        hash: &str,
+        ttl: &env_toml::Ttl,
+    ) -> Result<(), Error> {
+        let contract = soroban_cli::utils::contract_id_from_str(contract_id)
+            .map_err(|_| Error::InvalidContractId(contract_id.to_string()))?;
+        let mut wasm_hash = [0u8; 32];
+        hex::decode_to_slice(hash, &mut wasm_hash)?;
+        let keys = [
+            LedgerKey::ContractData(LedgerKeyContractData {
+                contract: ScAddress::Contract(xdr::Hash(contract)),
+                key: ScVal::LedgerKeyContractInstance,
+                durability: ContractDataDurability::Persistent,
+            }),
+            LedgerKey::ContractCode(LedgerKeyContractCode {
+                hash: xdr::Hash(wasm_hash),
+            }),
+        ];
+        let entries = soroban_rpc::Client::new(rpc_url)?
+            .get_full_ledger_entries(&keys)
+            .await?;
+
+        for entry in entries.entries {
+            let ledgers_left = i64::from(entry.live_until_ledger_seq) - entries.latest_ledger;
+            if ledgers_left >= i64::from(ttl.threshold) {
+                continue;
+            }
+            let mut extend = cli::contract::extend::Cmd::parse_arg_vec(&[
+                "--id",
+                contract_id,
+                "--durability",
+                "persistent",
+                "--ledgers-to-extend",
+                &ttl.extend_to.to_string(),
+            ])?;
+            // `contract extend` resolves `--id` even when extending Wasm, which clap won't allow
+            // alongside `--wasm-hash`, so the hash is set after parsing
+            let entry_name = match entry.key {
+                LedgerKey::ContractCode(_) => {
+                    extend.key.wasm_hash = Some(hash.to_string());
+                    "wasm"
+                }
+                _ => "instance",
+            };
+            let extended_to = extend
+                .run_against_rpc_server(None, None)
+                .await?
+                .into_result()
+                .expect("no TTL returned by 'contract extend'");
+            eprintln!(
+                "⏳ extended {name:?} {entry_name} TTL, which had {ledgers_left} ledgers left, to ledger {extended_to}"
+            );
+        }
+        Ok(())
+    }
+
+    /// Run the steps of a contract's `init` script that have not run for `contract_id` yet. Unless
+    /// it was just deployed, a contract is only initialized if Loam initialized it before, so
+    /// contracts deployed some other way are not initialized twice.
+    async fn run_init_script(
+        &self,
+        workspace_root: &std::path::Path,
+        name: &str,
+        contract_id: &str,
+        init_script: &str,
+        deployed: bool,
+    ) -> Result<(), Error> {
+        let mut record = init_script::Record::load(workspace_root)?;
+        if !deployed && !record.knows(contract_id) {
+            return Ok(());
+        }
+        // Contracts deployed earlier in this build only have IDs now
+        let init_script = env_toml::resolve_contract_ids(init_script, |contract| {
+            Self::get_contract_alias(contract).ok().flatten()
+        })?;
+        let steps = init_script::parse(&init_script)?
+            .into_iter()
+            .filter(|step| !record.has_run(contract_id, step))
+            .collect::<Vec<_>>();
+        if steps.is_empty() {
+            return Ok(());
+        }
+        eprintln!("🚀 Running initialization script for {name:?}");
+        record.start(contract_id)?;
+        for step in steps {
+            let function_args =
+                step.resolve_aliases(|alias| Self::get_contract_alias(alias).ok().flatten());
+            let mut args = vec!["--id", contract_id];
+            if let Some(account) = &step.source_account {
                 args.push("--source-account");
                 args.push(account);
             }
+            args.push("--");
+            args.extend(function_args.iter().map(String::as_str));
 
-            args.extend(&command_parts);
-
-            eprintln!("  ↳ Executing: soroban contract invoke {}", args.join(" "));
+            eprintln!(
+                "  ↳ Executing: soroban contract invoke {}",
+                shlex::try_join(args.iter().copied()).unwrap_or_else(|_| args.join(" "))
+            );
             let result = cli::contract::invoke::Cmd::parse_arg_vec(&args)?
                 .run_against_rpc_server(None, None)
                 .await?;
             eprintln!("  ↳ Result: {result:?}");
+            record.ran(contract_id, &step)?;
         }
         eprintln!("✅ Initialization script for {name:?} completed successfully");
         Ok(())
     }
 }
+
+/// Replace each `{{variable}}` in `template` with its value from `var`, leaving any other `{{...}}`,
+/// such as JSX's `style={{...}}`, as it is
+fn render(template: &str, var: impl Fn(&str) -> Option<String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        let variable = rest[start + 2..start + end].trim();
+        match var(variable) {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// `contract install` and `contract deploy` arguments for a contract's `source-account`, `fee` and
+/// `instructions` settings
+fn deploy_options(settings: Option<&env_toml::Contract>) -> Vec<String> {
+    let Some(settings) = settings else {
+        return Vec::new();
+    };
+    let mut options = Vec::new();
+    if let Some(account) = &settings.source_account {
+        options.extend(["--source-account".to_string(), account.clone()]);
+    }
+    if let Some(fee) = settings.fee {
+        options.extend(["--fee".to_string(), fee.to_string()]);
+    }
+    if let Some(instructions) = settings.instructions {
+        options.extend(["--instructions".to_string(), instructions.to_string()]);
+    }
+    options
+}
+
+/// Names of the workspace's contracts in the order to deploy them, each after the contracts it
+/// depends on with `depends-on` or as a Cargo dependency. `packages` are already sorted by their
+/// Cargo dependencies, and otherwise keep their order.
+fn deploy_order(
+    packages: &[Package],
+    current_env: &env_toml::Environment,
+) -> Result<Vec<String>, Error> {
+    let contract_name = |package: &str| package.replace('-', "_");
+    let mut pending = packages
+        .iter()
+        .map(|package| {
+            let name = contract_name(&package.name);
+            let mut depends_on = package
+                .dependencies
+                .iter()
+                .filter(|dep| {
+                    dep.kind == DependencyKind::Normal
+                        && packages
+                            .iter()
+                            .any(|p| p.name == dep.name && p.is_dep(&DepKind::Contract))
+                })
+                .map(|dep| contract_name(&dep.name))
+                .collect::<Vec<_>>();
+            let settings = current_env
+                .contracts
+                .as_ref()
+                .and_then(|contracts| contracts.get(name.as_str()));
+            for dependency in settings.iter().flat_map(|c| &c.depends_on) {
+                if packages
+                    .iter()
+                    .any(|p| contract_name(&p.name) == *dependency)
+                {
+                    depends_on.push(dependency.clone());
+                    continue;
+                }
+                // Contracts with an `id` or `address` are checked or spooned before any deploys
+                let elsewhere = current_env
+                    .contracts
+                    .as_ref()
+                    .and_then(|contracts| contracts.get(dependency.as_str()))
+                    .is_some_and(|c| c.id.is_some() || c.address.is_some());
+                if !elsewhere {
+                    return Err(Error::UnknownDependency {
+                        name,
+                        dependency: dependency.clone(),
+                    });
+                }
+            }
+            Ok((name, depends_on))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut ordered: Vec<String> = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let Some(next) = pending
+            .iter()
+            .position(|(_, depends_on)| depends_on.iter().all(|dep| ordered.contains(dep)))
+        else {
+            return Err(Error::DependencyCycle(
+                pending.into_iter().map(|(name, _)| name).collect(),
+            ));
+        };
+        ordered.push(pending.remove(next).0);
+    }
+    Ok(ordered)
+}
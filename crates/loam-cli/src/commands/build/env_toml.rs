@@ -1,63 +1,600 @@
+use serde::Deserialize;
+use soroban_cli::commands as cli;
 use std::collections::BTreeMap as Map;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("⛔ ️parsing environments.toml: {0}")]
     ParsingToml(io::Error),
-    #[error("⛔ ️no settings for current LOAM_ENV ({0:?}) found in environments.toml")]
-    NoSettingsForCurrentEnv(String),
+    #[error("⛔ ️no settings for current LOAM_ENV ({0:?}) found in environments.toml{1}")]
+    NoSettingsForCurrentEnv(String, String),
+    #[error("⛔ ️invalid environments.toml: {0}")]
+    Invalid(toml::de::Error),
+    #[error("⛔ ️environment {env:?} extends {parent:?}, which is not in environments.toml")]
+    UnknownParent { env: String, parent: String },
+    #[error("⛔ ️environments extend each other in a cycle: {}", .0.join(" → "))]
+    ExtendsCycle(Vec<String>),
+    #[error("⛔ ️${{{var}}} in environment {env:?} is not set in the environment or in .env")]
+    UndefinedVariable { env: String, var: String },
+    #[error("⛔ ️no ID for contract {0:?} referenced as ${{contracts.{0}.id}}")]
+    UnknownContractId(String),
 }
 
-type Environments = Map<Box<str>, Environment>;
+pub type Environments = Map<Box<str>, Environment>;
 
 #[derive(Debug, serde::Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct Environment {
+    /// Environment whose settings this one is deep-merged onto
+    pub extends: Option<String>,
     pub accounts: Option<Vec<Account>>,
+    #[serde(default)]
     pub network: Network,
     pub contracts: Option<Map<Box<str>, Contract>>,
+    #[serde(default)]
+    pub ttl: Ttl,
+    #[serde(default)]
+    pub clients: Clients,
+    /// Run contracts' `init` scripts after deploying them; by default only in `development` and
+    /// `testing`
+    pub run_init: Option<bool>,
+    /// Let the imported clients connect to RPC servers over plain HTTP; by default only in
+    /// `development`
+    pub allow_http: Option<bool>,
+    /// Redeploy contracts whose Wasm changed, and spoon contracts; by default everywhere but
+    /// `production`
+    pub allow_updates: Option<bool>,
+    /// Spoon contracts into a network that isn't local: one that is not standalone, and whose RPC
+    /// server is not on localhost
+    pub allow_live_spoon: Option<bool>,
 }
 
-#[derive(Debug, serde::Deserialize, Clone)]
-#[serde(rename_all = "kebab-case")]
+#[derive(Debug, serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct Network {
     pub name: Option<String>,
     pub rpc_url: Option<String>,
     pub network_passphrase: Option<String>,
-    // run_locally: Option<bool>,
+    /// Package Manager contract that `loam publish` publishes to
+    pub package_manager: Option<String>,
+    /// Contract Registry that `loam deploy` deploys through
+    pub registry: Option<String>,
+    /// Run an in-process sandbox network at `rpc-url`, with its ledger kept in `target/loam/`
+    #[serde(default)]
+    pub run_locally: bool,
+}
+
+/// When `loam build` extends the TTL of deployed contract instances and Wasm, in ledgers
+#[derive(Debug, serde::Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Ttl {
+    /// Extend entries with fewer ledgers than this left to live
+    pub threshold: u32,
+    /// Number of ledgers to extend entries' TTL to
+    pub extend_to: u32,
+}
+
+impl Default for Ttl {
+    /// Extend entries with less than ~30 days left to ~90 days, at 5 second ledgers
+    fn default() -> Self {
+        Self {
+            threshold: 518_400,
+            extend_to: 1_555_200,
+        }
+    }
+}
+
+/// How the clients the frontend imports each contract with are generated
+#[derive(Debug, serde::Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Clients {
+    /// Template for each contract's client, relative to the workspace root. `{{name}}`,
+    /// `{{contract_id}}`, `{{network_passphrase}}`, `{{rpc_url}}`, `{{env}}` and `{{allow_http}}`
+    /// are replaced with their values, and the file extension left after removing `.tmpl` is
+    /// used for the clients.
+    pub template: Option<PathBuf>,
+    /// Directory to write clients to, relative to the workspace root
+    #[serde(default = "default_clients_dir")]
+    pub out_dir: PathBuf,
+    /// Also write an `index` re-exporting every client under its contract's name
+    #[serde(default)]
+    pub index: bool,
+}
+
+fn default_clients_dir() -> PathBuf {
+    PathBuf::from("src/contracts")
+}
+
+impl Default for Clients {
+    fn default() -> Self {
+        Self {
+            template: None,
+            out_dir: default_clients_dir(),
+            index: false,
+        }
+    }
+}
+
+impl Clients {
+    /// File extension of the clients: the template's, without `.tmpl`, or `ts`
+    pub fn extension(&self) -> String {
+        self.template
+            .as_ref()
+            .and_then(|template| template.file_name()?.to_str())
+            .map(|file_name| file_name.trim_end_matches(".tmpl"))
+            .and_then(|file_name| Some(Path::new(file_name).extension()?.to_str()?.to_string()))
+            .unwrap_or_else(|| "ts".to_string())
+    }
 }
 
 #[derive(Debug, serde::Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct Account {
     pub name: String,
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub default: bool,
+
+    /// Fund the account with the network's friendbot if it does not exist yet. Never done on
+    /// mainnet.
+    #[serde(default = "default_fund")]
+    pub fund: bool,
+
+    /// Environment variable holding the secret key or seed phrase to import this identity from,
+    /// instead of generating new keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_env: Option<String>,
+
+    /// Balance in stroops the account must hold on mainnet; defaults to 1 XLM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_balance: Option<i64>,
+}
+
+fn default_fund() -> bool {
+    true
 }
 
 #[derive(Debug, serde::Deserialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct Contract {
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub client: bool,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub init: Option<String>,
+
+    /// Contracts to deploy and initialize before this one, besides the Cargo dependencies it has
+    /// with `contract = true` in their `[package.metadata.loam]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// Hex salt of up to 32 bytes to deploy with, so the contract gets the same ID each time it
+    /// is deployed from the same account to a fresh network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+
+    /// Account to install and deploy the contract with, instead of the default account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_account: Option<String>,
+
+    /// Fee in stroops for installing and deploying the contract
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<u32>,
+
+    /// Instructions to allow installing and deploying the contract, instead of the simulated
+    /// number plus padding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<u32>,
+
+    /// ID or alias of the contract already deployed to this environment's network, to check and
+    /// bind instead of deploying it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Environment whose network to spoon this contract from, instead of building it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+
+    /// ID of the contract to spoon
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// Persistent and temporary storage keys to spoon, as base64 `ScVal` XDR. The instance
+    /// storage is always spooned, but other entries cannot be listed through RPC.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<String>,
+
+    /// Ledger snapshot to spoon from instead of the network of `environment`, such as the ones
+    /// written by the Soroban SDK's test utilities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<PathBuf>,
 }
 
 impl Environment {
+    /// Settings of environment `loam_env`, or `None` if there is no environments.toml
+    ///
+    /// Only this environment and those it `extends` are resolved, so variables used only by other
+    /// environments need not be set.
     pub fn get(workspace_root: &Path, loam_env: &str) -> Result<Option<Environment>, Error> {
-        let env_toml = workspace_root.join("environments.toml");
+        let Some(file) = File::read(workspace_root)? else {
+            return Ok(None);
+        };
+        if !file.environments.contains_key(loam_env) {
+            let suggestion = did_you_mean(loam_env, file.environments.keys().map(String::as_str))
+                .map(|env| format!("; did you mean {env:?}?"))
+                .unwrap_or_default();
+            return Err(Error::NoSettingsForCurrentEnv(
+                loam_env.to_string(),
+                suggestion,
+            ));
+        }
+        file.resolve(loam_env, true).map(Some)
+    }
+
+    /// Every environment in environments.toml, by name; empty if there is no such file
+    ///
+    /// Variables that are not set are left as `${VAR}`, as they only matter to the environment
+    /// in use, which `get` reports them for; `check_variables` reports them for every environment.
+    pub fn get_all(workspace_root: &Path) -> Result<Environments, Error> {
+        let Some(file) = File::read(workspace_root)? else {
+            return Ok(Environments::new());
+        };
+        file.environments
+            .keys()
+            .map(|name| Ok((name.as_str().into(), file.resolve(name, false)?)))
+            .collect()
+    }
+
+    /// Check that every variable used by any environment is set
+    pub fn check_variables(workspace_root: &Path) -> Result<(), Error> {
+        let Some(file) = File::read(workspace_root)? else {
+            return Ok(());
+        };
+        for name in file.environments.keys() {
+            file.resolve(name, true)?;
+        }
+        Ok(())
+    }
+
+    /// Give settings that aren't set what environments with these well-known names have always done
+    fn set_defaults(&mut self, name: &str) {
+        self.run_init
+            .get_or_insert(matches!(name, "development" | "testing"));
+        self.allow_http.get_or_insert(name == "development");
+        self.allow_updates.get_or_insert(name != "production");
+    }
+
+    /// ID of a contract in this environment: its `id` setting, or else the alias saved for it on
+    /// this environment's network
+    pub fn contract_id(&self, name: &str) -> Option<String> {
+        let passphrase = self.network.passphrase()?;
+        let id = self
+            .contracts
+            .as_ref()
+            .and_then(|contracts| contracts.get(name)?.id.clone());
+        let locator = cli::config::locator::Args {
+            global: false,
+            config_dir: None,
+        };
+        match id {
+            // an `id` referring to another contract that has no ID yet
+            Some(id) if id.contains("${") => None,
+            Some(id) => Some(
+                locator
+                    .get_contract_id(&id, &passphrase)
+                    .ok()
+                    .flatten()
+                    .unwrap_or(id),
+            ),
+            None => locator.get_contract_id(name, &passphrase).ok().flatten(),
+        }
+    }
+}
+
+impl Network {
+    /// Passphrase of the network, resolved without connecting to it
+    pub fn passphrase(&self) -> Option<String> {
+        match self {
+            Network {
+                run_locally: true,
+                network_passphrase,
+                ..
+            } => Some(
+                network_passphrase
+                    .clone()
+                    .unwrap_or_else(|| cli::network::LOCAL_NETWORK_PASSPHRASE.to_string()),
+            ),
+            Network {
+                name: Some(name), ..
+            } => cli::config::locator::Args {
+                global: false,
+                config_dir: None,
+            }
+            .read_network(name)
+            .ok()
+            .map(|network| network.network_passphrase),
+            Network {
+                network_passphrase, ..
+            } => network_passphrase.clone(),
+        }
+    }
+}
+
+/// Replace `${contracts.<name>.id}` references in `s` with IDs from `contract_id`, failing on any
+/// contract it has no ID for
+pub fn resolve_contract_ids(
+    s: &str,
+    contract_id: impl Fn(&str) -> Option<String>,
+) -> Result<String, Error> {
+    interpolate_str(s, &mut |reference| match contract_reference(reference) {
+        Some(contract) => contract_id(contract)
+            .map(Some)
+            .ok_or_else(|| Error::UnknownContractId(contract.to_string())),
+        None => Ok(None),
+    })
+}
 
+/// environments.toml as written, and the variables in `.env`
+struct File {
+    source: String,
+    environments: toml::Table,
+    dotenv: Map<String, String>,
+}
+
+impl File {
+    fn read(workspace_root: &Path) -> Result<Option<Self>, Error> {
+        let env_toml = workspace_root.join("environments.toml");
         if !env_toml.exists() {
             return Ok(None);
         }
+        let source = std::fs::read_to_string(env_toml).map_err(Error::ParsingToml)?;
+        let environments = toml::from_str(&source).map_err(Error::Invalid)?;
+        Ok(Some(Self {
+            source,
+            environments,
+            dotenv: dotenv(workspace_root),
+        }))
+    }
+
+    /// Settings of environment `name`
+    ///
+    /// They are deep-merged onto the environment it `extends`, then `${VAR}` is replaced with
+    /// variables from the process environment or `.env`, and `${contracts.<name>.id}` with the
+    /// contract's ID in that environment, if it has one yet. Only then are they deserialized, so
+    /// settings can be checked as they are used. A variable that is not set is an error if
+    /// `strict`, and is left as it is otherwise.
+    fn resolve(&self, name: &str, strict: bool) -> Result<Environment, Error> {
+        let mut env = extend(name, &self.environments, &mut Vec::new())?;
+        interpolate(&mut env, &mut |reference| {
+            if contract_reference(reference).is_some() {
+                return Ok(None);
+            }
+            match std::env::var(reference)
+                .ok()
+                .or_else(|| self.dotenv.get(reference).cloned())
+            {
+                Some(value) => Ok(Some(value)),
+                None if strict => Err(Error::UndefinedVariable {
+                    env: name.to_string(),
+                    var: reference.to_string(),
+                }),
+                None => Ok(None),
+            }
+        })?;
+        let ids = self.deserialize(env.clone())?;
+        interpolate(&mut env, &mut |reference| {
+            Ok(contract_reference(reference).and_then(|contract| ids.contract_id(contract)))
+        })?;
+        let mut env = self.deserialize(env)?;
+        env.set_defaults(name);
+        Ok(env)
+    }
+
+    /// Deserialize resolved settings, reporting mistakes made in environments.toml itself with
+    /// their location
+    fn deserialize(&self, env: toml::Value) -> Result<Environment, Error> {
+        Environment::deserialize(env).map_err(|e| {
+            Error::Invalid(
+                toml::from_str::<Environments>(&self.source)
+                    .err()
+                    .unwrap_or(e),
+            )
+        })
+    }
+}
+
+/// Settings of environment `name`, deep-merged onto those of the environment it `extends`
+fn extend(name: &str, raw: &toml::Table, chain: &mut Vec<String>) -> Result<toml::Value, Error> {
+    chain.push(name.to_string());
+    let mut env = raw[name].clone();
+    let parent = env.as_table_mut().and_then(|env| env.remove("extends"));
+    if let Some(parent) = parent.as_ref().and_then(toml::Value::as_str) {
+        if chain.iter().any(|env| env == parent) {
+            chain.push(parent.to_string());
+            return Err(Error::ExtendsCycle(chain.clone()));
+        }
+        if !raw.contains_key(parent) {
+            return Err(Error::UnknownParent {
+                env: name.to_string(),
+                parent: parent.to_string(),
+            });
+        }
+        env = merge(extend(parent, raw, chain)?, env);
+    }
+    Ok(env)
+}
+
+/// `over` with tables merged key by key onto `base`, and anything else replacing it
+fn merge(base: toml::Value, over: toml::Value) -> toml::Value {
+    match (base, over) {
+        (toml::Value::Table(mut base), toml::Value::Table(over)) => {
+            for (key, value) in over {
+                let value = match base.remove(&key) {
+                    Some(base) => merge(base, value),
+                    None => value,
+                };
+                base.insert(key, value);
+            }
+            toml::Value::Table(base)
+        }
+        (_, over) => over,
+    }
+}
+
+/// Replace `${...}` references in every string in `value` with what `resolve` returns for them,
+/// leaving those it returns `None` for
+fn interpolate(
+    value: &mut toml::Value,
+    resolve: &mut dyn FnMut(&str) -> Result<Option<String>, Error>,
+) -> Result<(), Error> {
+    match value {
+        toml::Value::String(s) => *s = interpolate_str(s, resolve)?,
+        toml::Value::Array(values) => {
+            for value in values {
+                interpolate(value, resolve)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                interpolate(value, resolve)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_str(
+    s: &str,
+    resolve: &mut dyn FnMut(&str) -> Result<Option<String>, Error>,
+) -> Result<String, Error> {
+    let mut interpolated = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let reference = &rest[start + 2..start + len];
+        interpolated.push_str(&rest[..start]);
+        match resolve(reference)? {
+            Some(value) => interpolated.push_str(&value),
+            None => interpolated.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    interpolated.push_str(rest);
+    Ok(interpolated)
+}
+
+/// Name of the contract in a `contracts.<name>.id` reference
+pub(crate) fn contract_reference(reference: &str) -> Option<&str> {
+    reference.strip_prefix("contracts.")?.strip_suffix(".id")
+}
+
+/// Variables set in the workspace's `.env` file, if it has one
+fn dotenv(workspace_root: &Path) -> Map<String, String> {
+    let contents = std::fs::read_to_string(workspace_root.join(".env")).unwrap_or_default();
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, value) = line.trim_start_matches("export ").split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                })
+                .unwrap_or(value);
+            Some((name.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// The candidate closest to a misspelled `name`, if any is close enough to suggest
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::levenshtein(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= name.len().max(3) / 3)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(environments_toml: &str) -> assert_fs::TempDir {
+        let dir = assert_fs::TempDir::new().unwrap();
+        std::fs::write(dir.join("environments.toml"), environments_toml).unwrap();
+        dir
+    }
+
+    #[test]
+    fn only_resolve_the_current_environment() {
+        let dir = workspace(
+            r#"
+[development]
+network = { rpc-url = "http://localhost:8000/rpc", network-passphrase = "${LOAM_TEST_PASSPHRASE}" }
+
+[production]
+extends = "development"
+network = { rpc-url = "${LOAM_TEST_UNSET_RPC_URL}" }
+"#,
+        );
+        std::fs::write(
+            dir.join(".env"),
+            "LOAM_TEST_PASSPHRASE='Standalone Network ; February 2017'\n",
+        )
+        .unwrap();
+
+        let development = Environment::get(&dir, "development").unwrap().unwrap();
+        assert_eq!(
+            development.network.network_passphrase.as_deref(),
+            Some("Standalone Network ; February 2017")
+        );
+        assert!(matches!(
+            Environment::get(&dir, "production"),
+            Err(Error::UndefinedVariable { env, var })
+                if env == "production" && var == "LOAM_TEST_UNSET_RPC_URL"
+        ));
+        assert!(matches!(
+            Environment::check_variables(&dir),
+            Err(Error::UndefinedVariable { .. })
+        ));
+
+        let all = Environment::get_all(&dir).unwrap();
+        assert_eq!(
+            all["production"].network.rpc_url.as_deref(),
+            Some("${LOAM_TEST_UNSET_RPC_URL}")
+        );
+        assert_eq!(
+            all["production"].network.network_passphrase.as_deref(),
+            Some("Standalone Network ; February 2017")
+        );
+    }
 
-        let toml_str = std::fs::read_to_string(env_toml).map_err(Error::ParsingToml)?;
-        let mut parsed_toml: Environments = toml::from_str(&toml_str).unwrap();
-        let current_env = parsed_toml.remove(loam_env);
-        if current_env.is_none() {
-            return Err(Error::NoSettingsForCurrentEnv(loam_env.to_string()));
+    #[test]
+    fn report_mistakes_with_their_location() {
+        let dir = workspace(
+            r"
+[development]
+network = { run-localy = true }
+",
+        );
+        let Err(Error::Invalid(error)) = Environment::get(&dir, "development") else {
+            panic!("expected an invalid environments.toml");
         };
-        Ok(current_env)
+        assert!(error.to_string().contains("line 3"), "{error}");
+        assert!(error.to_string().contains("unknown field `run-localy`"));
     }
 }
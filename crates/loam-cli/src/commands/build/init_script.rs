@@ -0,0 +1,259 @@
+//! Contracts' `init` scripts: splitting them into steps, and recording which steps already ran.
+//!
+//! Each step is the arguments of one `contract invoke`, split like a shell would, so arguments
+//! can be quoted. A step continues onto the next line while a quote is open or the line ends with
+//! `\`, which lets JSON arguments span several lines in single quotes. Lines starting with `#` are
+//! comments. The steps run for each contract ID are kept in `target/loam/init.json`, so a build
+//! only runs the ones it has not run yet.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("⛔ ️unterminated quote in init step:\n{0}")]
+    UnterminatedQuote(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Account to invoke as, from a `STELLAR_ACCOUNT=<name>` word
+    pub source_account: Option<String>,
+    /// Function name and arguments to pass after `--`
+    pub args: Vec<String>,
+}
+
+impl Step {
+    /// How the step is written, quoted where needed; also its key in the record of what ran
+    pub fn text(&self) -> String {
+        let words = self
+            .source_account
+            .iter()
+            .map(|account| format!("STELLAR_ACCOUNT={account}"))
+            .chain(self.args.iter().cloned())
+            .collect::<Vec<_>>();
+        shlex::try_join(words.iter().map(String::as_str)).unwrap_or_else(|_| words.join(" "))
+    }
+
+    /// Replace `$<alias>` in the arguments with the ID of the contract saved as `<alias>`, leaving
+    /// any other `$`, such as in `$HOME` or `$5`, as it is
+    pub fn resolve_aliases(&self, contract_id: impl Fn(&str) -> Option<String>) -> Vec<String> {
+        self.args
+            .iter()
+            .map(|arg| {
+                let mut resolved = String::with_capacity(arg.len());
+                let mut rest = arg.as_str();
+                while let Some(start) = rest.find('$') {
+                    resolved.push_str(&rest[..=start]);
+                    rest = &rest[start + 1..];
+                    let end = rest
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                        .unwrap_or(rest.len());
+                    let alias = &rest[..end];
+                    if let Some(id) = (!alias.is_empty()).then(|| contract_id(alias)).flatten() {
+                        resolved.pop();
+                        resolved.push_str(&id);
+                        rest = &rest[end..];
+                    }
+                }
+                resolved.push_str(rest);
+                resolved
+            })
+            .collect()
+    }
+}
+
+/// Split an `init` script into its steps
+pub fn parse(script: &str) -> Result<Vec<Step>, Error> {
+    let mut steps = Vec::new();
+    let mut pending = String::new();
+    for line in script.lines() {
+        if let Some(continued) = line.trim_end().strip_suffix('\\') {
+            pending.push_str(continued);
+            pending.push(' ');
+            continue;
+        }
+        pending.push_str(line);
+        pending.push('\n');
+        let Some(words) = shlex::split(&pending) else {
+            continue;
+        };
+        pending.clear();
+        if words.is_empty() {
+            continue;
+        }
+        let (accounts, args): (Vec<_>, Vec<_>) = words
+            .into_iter()
+            .partition(|word| word.starts_with("STELLAR_ACCOUNT="));
+        let source_account = accounts
+            .last()
+            .map(|word| word["STELLAR_ACCOUNT=".len()..].to_string());
+        steps.push(Step {
+            source_account,
+            args,
+        });
+    }
+    if !pending.trim().is_empty() {
+        return Err(Error::UnterminatedQuote(pending.trim().to_string()));
+    }
+    Ok(steps)
+}
+
+/// Steps already run for each contract ID, saved in `target/loam/init.json`
+#[derive(Debug, Default)]
+pub struct Record {
+    path: PathBuf,
+    ran: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Record {
+    pub fn load(workspace_root: &Path) -> Result<Self, Error> {
+        let path = workspace_root.join("target/loam/init.json");
+        let ran = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, ran })
+    }
+
+    /// Whether Loam started initializing the contract at `contract_id`
+    pub fn knows(&self, contract_id: &str) -> bool {
+        self.ran.contains_key(contract_id)
+    }
+
+    pub fn has_run(&self, contract_id: &str, step: &Step) -> bool {
+        self.ran
+            .get(contract_id)
+            .is_some_and(|steps| steps.contains(&step.text()))
+    }
+
+    /// Start initializing the contract at `contract_id`, so steps added to its script later are
+    /// run on following builds
+    pub fn start(&mut self, contract_id: &str) -> Result<(), Error> {
+        self.ran.entry(contract_id.to_string()).or_default();
+        self.save()
+    }
+
+    pub fn ran(&mut self, contract_id: &str, step: &Step) -> Result<(), Error> {
+        self.ran
+            .entry(contract_id.to_string())
+            .or_default()
+            .insert(step.text());
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.ran)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(source_account: Option<&str>, args: &[&str]) -> Step {
+        Step {
+            source_account: source_account.map(ToString::to_string),
+            args: args.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn ids(alias: &str) -> Option<String> {
+        (alias == "token").then(|| "CTOKEN".to_string())
+    }
+
+    #[test]
+    fn parse_steps() {
+        let steps = parse(
+            r"
+# set up the admin first
+admin_set --new-admin alice
+
+STELLAR_ACCOUNT=bob increment # bob's first increment
+",
+        )
+        .unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                step(None, &["admin_set", "--new-admin", "alice"]),
+                step(Some("bob"), &["increment"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_quotes_across_lines() {
+        let steps = parse(
+            r#"
+init --config '{
+    "quorum": 100,
+    "voting_period": 10
+}'
+increment
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                step(
+                    None,
+                    &[
+                        "init",
+                        "--config",
+                        "{\n    \"quorum\": 100,\n    \"voting_period\": 10\n}"
+                    ]
+                ),
+                step(None, &["increment"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_continued_lines() {
+        let steps = parse("mint \\\n  --amount 100 \\\n  --to alice\nincrement").unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                step(None, &["mint", "--amount", "100", "--to", "alice"]),
+                step(None, &["increment"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_unterminated_quote() {
+        assert!(matches!(
+            parse("init --name 'hello\nincrement"),
+            Err(Error::UnterminatedQuote(step)) if step == "init --name 'hello\nincrement"
+        ));
+    }
+
+    #[test]
+    fn resolve_aliases() {
+        let resolved =
+            step(None, &["mint", "--to", "$token", "--memo", "$token,$token"]).resolve_aliases(ids);
+        assert_eq!(
+            resolved,
+            vec!["mint", "--to", "CTOKEN", "--memo", "CTOKEN,CTOKEN"]
+        );
+    }
+
+    #[test]
+    fn leave_other_dollar_signs() {
+        let args = [
+            "set", "--path", "$HOME/x", "--price", "$5 or $$", "--to", "$tokn", "$",
+        ];
+        assert_eq!(step(None, &args).resolve_aliases(ids), args);
+    }
+}
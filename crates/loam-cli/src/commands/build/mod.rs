@@ -14,6 +14,9 @@ use std::{
 
 pub mod clients;
 pub mod env_toml;
+pub mod init_script;
+pub mod sandbox;
+pub mod spoon;
 
 /// Build a contract from source
 ///
@@ -121,9 +124,8 @@ impl Cmd {
             }
         }
 
-        let mut package_names: Vec<String> = Vec::new();
-        for p in packages {
-            package_names.push(p.name.clone().replace('-', "_"));
+        let git_hash = git_hash(metadata.workspace_root.as_std_path());
+        for p in &packages {
             let mut cmd = Command::new("cargo");
             cmd.stdout(Stdio::piped());
             cmd.arg("rustc");
@@ -158,6 +160,9 @@ impl Cmd {
             if self.profile.is_none() {
                 set_default_profile_flags(&mut cmd);
             }
+            if let Some(git_hash) = &git_hash {
+                cmd.env("LOAM_GIT_HASH", git_hash);
+            }
             let cmd_str = format!(
                 "cargo {}",
                 cmd.get_args().map(OsStr::to_string_lossy).join(" ")
@@ -193,7 +198,7 @@ impl Cmd {
 
         if self.build_clients {
             self.build_clients_args
-                .run(&metadata.workspace_root.into_std_path_buf(), package_names)
+                .run(&metadata.workspace_root.into_std_path_buf(), &packages)
                 .await?;
         }
 
@@ -234,7 +239,7 @@ impl Cmd {
             .collect())
     }
 
-    fn metadata(&self) -> Result<Metadata, cargo_metadata::Error> {
+    pub fn metadata(&self) -> Result<Metadata, cargo_metadata::Error> {
         let mut cmd = MetadataCommand::new();
         cmd.no_deps();
         cmd.manifest_path(&self.manifest_path);
@@ -245,6 +250,28 @@ impl Cmd {
     }
 }
 
+/// The commit checked out in the workspace, which `derive_contract` embeds in the contract's metadata
+///
+/// Marked `-dirty` when tracked files have uncommitted changes, since the Wasm may then not match
+/// the commit.
+fn git_hash(workspace_root: &Path) -> Option<String> {
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .current_dir(workspace_root)
+            .args(args)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    };
+    let hash = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+        .is_some_and(|status| !status.trim().is_empty());
+    Some(if dirty { format!("{hash}-dirty") } else { hash })
+}
+
 fn set_default_profile_flags(cmd: &mut Command) {
     cmd.args([
         "--",
@@ -0,0 +1,1135 @@
+//! Ledger state of the sandbox network, and the RPC methods that read and change it
+
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use soroban_env_host::{
+    budget::Budget,
+    e2e_invoke::{self, LedgerEntryChange as HostLedgerChange},
+    storage::SnapshotSource,
+    HostError, LedgerInfo,
+};
+use soroban_ledger_snapshot::LedgerSnapshot;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use stellar_xdr::curr::{
+    AccountEntry, AccountEntryExt, AccountId, ContractDataDurability, ContractEvent,
+    DiagnosticEvent, ExtendFootprintTtlResult, ExtensionPoint, Hash, InvokeHostFunctionOp,
+    InvokeHostFunctionResult, LedgerEntry, LedgerEntryChange, LedgerEntryChanges, LedgerEntryData,
+    LedgerEntryExt, LedgerFootprint, LedgerKey, LedgerKeyAccount, LedgerKeyContractData, Limits,
+    MuxedAccount, MuxedAccountMed25519, OperationBody, OperationMeta, OperationResult,
+    OperationResultTr, PublicKey, ReadXdr, RestoreFootprintResult, ScVal, SequenceNumber,
+    SorobanResources, SorobanTransactionData, SorobanTransactionMeta, SorobanTransactionMetaExt,
+    String32, Thresholds, Transaction, TransactionEnvelope, TransactionExt, TransactionMeta,
+    TransactionMetaV3, TransactionResult, TransactionResultExt, TransactionResultResult,
+    TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
+    TransactionV1Envelope, TtlEntry, Uint256, VecM, WriteXdr,
+};
+
+use super::Error;
+
+/// Protocol of the Soroban host the sandbox runs transactions with
+const PROTOCOL_VERSION: u32 = 21;
+/// Seconds between ledgers, as on the public networks
+const LEDGER_CLOSE_TIME: u64 = 5;
+/// Balance of the accounts created by the sandbox's friendbot, 10,000 XLM
+const FRIENDBOT_BALANCE: i64 = 100_000_000_000;
+
+/// Error of an RPC method, answered as a JSON-RPC error
+#[derive(thiserror::Error, Debug)]
+pub enum RpcError {
+    /// The request's params are missing or malformed
+    #[error("{0}")]
+    InvalidParams(String),
+    /// The sandbox could not apply a well-formed request, such as when saving the ledger fails
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl RpcError {
+    /// JSON-RPC error code
+    pub fn code(&self) -> i64 {
+        match self {
+            RpcError::InvalidParams(_) => -32602,
+            RpcError::Internal(_) => -32603,
+        }
+    }
+}
+
+impl From<&str> for RpcError {
+    fn from(message: &str) -> Self {
+        RpcError::InvalidParams(message.to_string())
+    }
+}
+
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        RpcError::InvalidParams(message)
+    }
+}
+
+/// Result of an RPC method
+pub type RpcResult<T = Value> = Result<T, RpcError>;
+
+type Entries = BTreeMap<LedgerKey, (LedgerEntry, Option<u32>)>;
+
+pub struct Ledger {
+    path: PathBuf,
+    passphrase: String,
+    info: LedgerInfo,
+    entries: Entries,
+    /// `getTransaction` responses by transaction hash
+    transactions: HashMap<String, Value>,
+}
+
+impl Ledger {
+    /// Load the ledger saved at `path`, or start a new one for the network with `passphrase`
+    pub fn load(path: PathBuf, passphrase: &str) -> Result<Self, Error> {
+        let network_id: [u8; 32] = Sha256::digest(passphrase.as_bytes()).into();
+        let (info, entries) = if path.exists() {
+            let snapshot = LedgerSnapshot::read_file(&path)?;
+            if snapshot.network_id != network_id {
+                return Err(Error::NetworkMismatch(path));
+            }
+            let info = snapshot.ledger_info();
+            let entries = snapshot
+                .ledger_entries
+                .into_iter()
+                .map(|(key, (entry, live_until))| (*key, (*entry, live_until)))
+                .collect();
+            (info, entries)
+        } else {
+            let info = LedgerInfo {
+                protocol_version: PROTOCOL_VERSION,
+                sequence_number: 1,
+                timestamp: now(),
+                network_id,
+                base_reserve: 5_000_000,
+                min_temp_entry_ttl: 16,
+                min_persistent_entry_ttl: 4096,
+                max_entry_ttl: 3_110_400,
+            };
+            (info, Entries::new())
+        };
+        Ok(Self {
+            path,
+            passphrase: passphrase.to_string(),
+            info,
+            entries,
+            transactions: HashMap::new(),
+        })
+    }
+
+    fn save(&self) -> RpcResult<()> {
+        let mut snapshot = LedgerSnapshot::default();
+        snapshot.set_ledger_info(self.info.clone());
+        snapshot.ledger_entries = self
+            .entries
+            .iter()
+            .map(|(key, (entry, live_until))| {
+                (
+                    Box::new(key.clone()),
+                    (Box::new(entry.clone()), *live_until),
+                )
+            })
+            .collect();
+        snapshot
+            .write_file(&self.path)
+            .map_err(|e| RpcError::Internal(format!("saving {}: {e}", self.path.display())))
+    }
+
+    /// Close `count` ledgers, moving time forward at least as far as the ledgers would take, and
+    /// dropping expired temporary entries
+    fn close_ledgers(&mut self, count: u32) {
+        self.info.sequence_number = self.info.sequence_number.saturating_add(count);
+        self.info.timestamp =
+            (self.info.timestamp + LEDGER_CLOSE_TIME * u64::from(count)).max(now());
+        let sequence = self.info.sequence_number;
+        self.entries.retain(|key, (_, live_until)| {
+            !is_temporary(key) || live_until.map_or(true, |live_until| live_until >= sequence)
+        });
+    }
+
+    pub fn get_health(&self) -> Value {
+        json!({
+            "status": "healthy",
+            "latestLedger": self.info.sequence_number,
+            "oldestLedger": 1,
+            "ledgerRetentionWindow": self.info.sequence_number,
+        })
+    }
+
+    pub fn get_network(&self, friendbot_url: &str) -> Value {
+        json!({
+            "friendbotUrl": friendbot_url,
+            "passphrase": self.passphrase,
+            "protocolVersion": PROTOCOL_VERSION,
+            "sandbox": "Loam's local network: transactions are applied without checking signatures or charging fees",
+        })
+    }
+
+    pub fn get_latest_ledger(&self) -> Value {
+        let sequence = self.info.sequence_number;
+        json!({
+            "id": hex::encode(Sha256::digest(sequence.to_be_bytes())),
+            "protocolVersion": PROTOCOL_VERSION,
+            "sequence": sequence,
+        })
+    }
+
+    pub fn get_ledger_entries(&self, params: &Value) -> RpcResult {
+        let keys = params
+            .get("keys")
+            .and_then(Value::as_array)
+            .ok_or("expected `keys`")?;
+        let mut entries = vec![];
+        for encoded in keys {
+            let encoded = encoded.as_str().ok_or("expected `keys` to be strings")?;
+            let key: LedgerKey = decode(encoded)?;
+            if let Some((entry, live_until)) = self.entries.get(&key) {
+                let mut result = Map::new();
+                result.insert("key".to_string(), encoded.into());
+                result.insert("xdr".to_string(), encode(&entry.data)?.into());
+                result.insert(
+                    "lastModifiedLedgerSeq".to_string(),
+                    entry.last_modified_ledger_seq.into(),
+                );
+                if let Some(live_until) = live_until {
+                    result.insert("liveUntilLedgerSeq".to_string(), (*live_until).into());
+                }
+                entries.push(Value::Object(result));
+            }
+        }
+        Ok(json!({
+            "entries": entries,
+            "latestLedger": self.info.sequence_number,
+        }))
+    }
+
+    pub fn get_transaction(&self, params: &Value) -> RpcResult {
+        let hash = params
+            .get("hash")
+            .and_then(Value::as_str)
+            .ok_or("expected `hash`")?;
+        let mut response = self
+            .transactions
+            .get(hash)
+            .cloned()
+            .unwrap_or_else(|| json!({ "status": "NOT_FOUND" }));
+        response["latestLedger"] = self.info.sequence_number.into();
+        response["latestLedgerCloseTime"] = self.info.timestamp.to_string().into();
+        response["oldestLedger"] = 1.into();
+        Ok(response)
+    }
+
+    /// Create and fund an account, the way friendbot does on test networks
+    pub fn fund(&mut self, address: &str) -> RpcResult {
+        let account_id = AccountId::from_str(address).map_err(|e| format!("{address}: {e}"))?;
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: account_id.clone(),
+        });
+        if self.entries.contains_key(&key) {
+            return Ok(json!({
+                "status": 400,
+                "detail": "createAccountAlreadyExist",
+            }));
+        }
+        self.close_ledgers(1);
+        let sequence = self.info.sequence_number;
+        let account = AccountEntry {
+            account_id,
+            balance: FRIENDBOT_BALANCE,
+            seq_num: SequenceNumber(i64::from(sequence) << 32),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: 0,
+            home_domain: String32::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: VecM::default(),
+            ext: AccountEntryExt::V0,
+        };
+        let entry = LedgerEntry {
+            last_modified_ledger_seq: sequence,
+            data: LedgerEntryData::Account(account),
+            ext: LedgerEntryExt::V0,
+        };
+        self.entries.insert(key, (entry, None));
+        self.save()?;
+        Ok(json!({ "successful": true, "ledger": sequence }))
+    }
+
+    /// Close `ledgers` empty ledgers
+    pub fn time_travel(&mut self, params: &Value) -> RpcResult {
+        let ledgers = params
+            .get("ledgers")
+            .and_then(Value::as_u64)
+            .and_then(|ledgers| u32::try_from(ledgers).ok())
+            .ok_or("expected a number of `ledgers` to close")?;
+        self.close_ledgers(ledgers);
+        self.save()?;
+        Ok(json!({
+            "sequence": self.info.sequence_number,
+            "timestamp": self.info.timestamp,
+        }))
+    }
+
+    pub fn simulate_transaction(&self, params: &Value) -> RpcResult {
+        let envelope: TransactionEnvelope = decode(
+            params
+                .get("transaction")
+                .and_then(Value::as_str)
+                .ok_or("expected `transaction`")?,
+        )?;
+        let tx = transaction(&envelope)?;
+        let [op] = tx.operations.as_slice() else {
+            return Err("only transactions with a single operation can be simulated".into());
+        };
+        match &op.body {
+            OperationBody::InvokeHostFunction(invoke) => {
+                let source = op.source_account.as_ref().unwrap_or(&tx.source_account);
+                self.simulate_invoke(&account_id(source), invoke, self.hash(tx)?)
+            }
+            OperationBody::ExtendFootprintTtl(_) | OperationBody::RestoreFootprint(_) => {
+                let TransactionExt::V1(data) = &tx.ext else {
+                    return Err("expected Soroban transaction data with a footprint".into());
+                };
+                Ok(json!({
+                    "transactionData": encode(data)?,
+                    "minResourceFee": "0",
+                    "latestLedger": self.info.sequence_number,
+                }))
+            }
+            _ => Err("only Soroban operations can be simulated".into()),
+        }
+    }
+
+    /// Run a host function in recording mode against the next ledger, to find its footprint and
+    /// the authorizations it needs
+    fn simulate_invoke(
+        &self,
+        source: &AccountId,
+        op: &InvokeHostFunctionOp,
+        seed: [u8; 32],
+    ) -> RpcResult {
+        let mut info = self.info.clone();
+        info.sequence_number += 1;
+        info.timestamp += LEDGER_CLOSE_TIME;
+        let snapshot = Rc::new(Simulation {
+            entries: self.entries.clone(),
+            sequence: info.sequence_number,
+            archived: RefCell::default(),
+        });
+        let auth = (!op.auth.is_empty()).then(|| op.auth.to_vec());
+        let budget = Budget::default();
+        let mut diagnostics = vec![];
+        let result = e2e_invoke::invoke_host_function_in_recording_mode(
+            &budget,
+            true,
+            &op.host_function,
+            source,
+            auth,
+            info,
+            snapshot.clone(),
+            seed,
+            &mut diagnostics,
+        );
+        let events = diagnostics
+            .iter()
+            .map(encode)
+            .collect::<RpcResult<Vec<_>>>()?;
+        let latest_ledger = self.info.sequence_number;
+        let recorded = match result {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                return Ok(
+                    json!({ "error": format!("{e:?}"), "events": events, "latestLedger": latest_ledger }),
+                )
+            }
+        };
+        let value = match recorded.invoke_result {
+            Ok(value) => value,
+            Err(e) => {
+                return Ok(
+                    json!({ "error": format!("{e:?}"), "events": events, "latestLedger": latest_ledger }),
+                )
+            }
+        };
+        let data = SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: recorded.resources,
+            resource_fee: 0,
+        };
+        let mut response = json!({
+            "transactionData": encode(&data)?,
+            "minResourceFee": "0",
+            "cost": {
+                "cpuInsns": budget.get_cpu_insns_consumed().unwrap_or_default().to_string(),
+                "memBytes": budget.get_mem_bytes_consumed().unwrap_or_default().to_string(),
+            },
+            "results": [{
+                "auth": recorded.auth.iter().map(encode).collect::<RpcResult<Vec<_>>>()?,
+                "xdr": encode(&value)?,
+            }],
+            "events": events,
+            "latestLedger": latest_ledger,
+        });
+        let archived = snapshot.archived.take();
+        if !archived.is_empty() {
+            let restore = SorobanTransactionData {
+                ext: ExtensionPoint::V0,
+                resources: SorobanResources {
+                    footprint: LedgerFootprint {
+                        read_only: VecM::default(),
+                        read_write: archived
+                            .into_iter()
+                            .collect::<Vec<_>>()
+                            .try_into()
+                            .map_err(|_| internal("footprint too large"))?,
+                    },
+                    instructions: 0,
+                    read_bytes: 0,
+                    write_bytes: 0,
+                },
+                resource_fee: 0,
+            };
+            response["restorePreamble"] = json!({
+                "transactionData": encode(&restore)?,
+                "minResourceFee": "0",
+            });
+        }
+        Ok(response)
+    }
+
+    /// Apply a transaction in a new ledger. Signatures are not checked and no fees are charged.
+    pub fn send_transaction(&mut self, params: &Value) -> RpcResult {
+        let encoded = params
+            .get("transaction")
+            .and_then(Value::as_str)
+            .ok_or("expected `transaction`")?;
+        let envelope: TransactionEnvelope = decode(encoded)?;
+        let tx = transaction(&envelope)?;
+        let seed = self.hash(tx)?;
+        let hash = hex::encode(seed);
+
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: account_id(&tx.source_account),
+        });
+        let rejection = match self.entries.get(&key) {
+            Some((
+                LedgerEntry {
+                    data: LedgerEntryData::Account(account),
+                    ..
+                },
+                _,
+            )) if tx.seq_num.0 != account.seq_num.0 + 1 => Some(TransactionResultResult::TxBadSeq),
+            Some(_) if tx.operations.len() != 1 => Some(TransactionResultResult::TxMalformed),
+            Some(_) => None,
+            None => Some(TransactionResultResult::TxNoAccount),
+        };
+        if let Some(result) = rejection {
+            return Ok(json!({
+                "status": "ERROR",
+                "hash": hash,
+                "errorResultXdr": encode(&transaction_result(result))?,
+                "latestLedger": self.info.sequence_number,
+                "latestLedgerCloseTime": self.info.timestamp.to_string(),
+            }));
+        }
+
+        self.close_ledgers(1);
+        let sequence = self.info.sequence_number;
+        if let Some((entry, _)) = self.entries.get_mut(&key) {
+            if let LedgerEntryData::Account(account) = &mut entry.data {
+                account.seq_num = tx.seq_num.clone();
+            }
+            entry.last_modified_ledger_seq = sequence;
+        }
+
+        let op = &tx.operations[0];
+        let source = op.source_account.as_ref().unwrap_or(&tx.source_account);
+        let data = match &tx.ext {
+            TransactionExt::V1(data) => Some(data),
+            TransactionExt::V0 => None,
+        };
+        let applied = match (&op.body, data) {
+            (OperationBody::InvokeHostFunction(invoke), Some(data)) => {
+                self.invoke(&account_id(source), invoke, data, seed)?
+            }
+            (OperationBody::ExtendFootprintTtl(op), Some(data)) => {
+                let changes = self.extend(op.extend_to, &data.resources.footprint)?;
+                Applied::success(
+                    OperationResultTr::ExtendFootprintTtl(ExtendFootprintTtlResult::Success),
+                    changes,
+                )
+            }
+            (OperationBody::RestoreFootprint(_), Some(data)) => {
+                let changes = self.restore(&data.resources.footprint)?;
+                Applied::success(
+                    OperationResultTr::RestoreFootprint(RestoreFootprintResult::Success),
+                    changes,
+                )
+            }
+            _ => Applied::failure(OperationResult::OpNotSupported),
+        };
+
+        let status = if applied.success { "SUCCESS" } else { "FAILED" };
+        let (result, meta) = applied.into_result_and_meta()?;
+        self.transactions.insert(
+            hash.clone(),
+            json!({
+                "status": status,
+                "applicationOrder": 1,
+                "ledger": sequence,
+                "createdAt": self.info.timestamp.to_string(),
+                "envelopeXdr": encoded,
+                "resultXdr": encode(&result)?,
+                "resultMetaXdr": encode(&meta)?,
+            }),
+        );
+        self.save()?;
+        Ok(json!({
+            "status": "PENDING",
+            "hash": hash,
+            "latestLedger": sequence,
+            "latestLedgerCloseTime": self.info.timestamp.to_string(),
+        }))
+    }
+
+    fn invoke(
+        &mut self,
+        source: &AccountId,
+        op: &InvokeHostFunctionOp,
+        data: &SorobanTransactionData,
+        seed: [u8; 32],
+    ) -> RpcResult<Applied> {
+        let sequence = self.info.sequence_number;
+        let footprint = &data.resources.footprint;
+        let mut ledger_entries = vec![];
+        let mut ttl_entries = vec![];
+        for key in footprint
+            .read_only
+            .iter()
+            .chain(footprint.read_write.iter())
+        {
+            let Some((entry, live_until)) = self.entries.get(key) else {
+                continue;
+            };
+            ledger_entries.push(to_xdr(entry)?);
+            ttl_entries.push(match live_until {
+                Some(live_until) if *live_until < sequence => {
+                    return Ok(Applied::failure(invoke_result(
+                        InvokeHostFunctionResult::EntryArchived,
+                    )));
+                }
+                Some(live_until) => to_xdr(&ttl(key, *live_until)?)?,
+                None => vec![],
+            });
+        }
+        let auth = op.auth.iter().map(to_xdr).collect::<RpcResult<Vec<_>>>()?;
+
+        let budget = Budget::default();
+        let mut diagnostics = vec![];
+        let result = e2e_invoke::invoke_host_function(
+            &budget,
+            true,
+            &to_xdr(&op.host_function)?,
+            &to_xdr(&data.resources)?,
+            &to_xdr(source)?,
+            auth.iter(),
+            self.info.clone(),
+            ledger_entries.iter(),
+            ttl_entries.iter(),
+            &seed.to_vec(),
+            &mut diagnostics,
+        );
+        let Ok(e2e_invoke::InvokeHostFunctionResult {
+            encoded_invoke_result: Ok(value),
+            ledger_changes,
+            encoded_contract_events: events,
+        }) = result
+        else {
+            let mut applied = Applied::failure(invoke_result(InvokeHostFunctionResult::Trapped));
+            applied.diagnostic_events = diagnostics;
+            return Ok(applied);
+        };
+        let changes = self.apply_changes(ledger_changes)?;
+        let mut applied = Applied::success(
+            OperationResultTr::InvokeHostFunction(InvokeHostFunctionResult::Success(Hash(
+                Sha256::digest(&value).into(),
+            ))),
+            changes,
+        );
+        applied.return_value = from_xdr(&value)?;
+        applied.events = events
+            .iter()
+            .map(|event| from_xdr(event))
+            .collect::<RpcResult<_>>()?;
+        applied.diagnostic_events = diagnostics;
+        Ok(applied)
+    }
+
+    /// Write the entries changed by a host function to the ledger
+    fn apply_changes(
+        &mut self,
+        ledger_changes: Vec<HostLedgerChange>,
+    ) -> RpcResult<Vec<LedgerEntryChange>> {
+        let sequence = self.info.sequence_number;
+        let mut changes = vec![];
+        for change in ledger_changes {
+            let key: LedgerKey = from_xdr(&change.encoded_key)?;
+            let new_live_until = change.ttl_change.map(|ttl| ttl.new_live_until_ledger);
+            let before = self.entries.get(&key).cloned();
+            if change.read_only {
+                if let (Some((_, Some(live_until))), Some(new_live_until)) =
+                    (self.entries.get_mut(&key), new_live_until)
+                {
+                    *live_until = (*live_until).max(new_live_until);
+                }
+                continue;
+            }
+            match change.encoded_new_value {
+                Some(value) => {
+                    let mut entry: LedgerEntry = from_xdr(&value)?;
+                    entry.last_modified_ledger_seq = match &before {
+                        Some((before, _)) if before.data == entry.data => {
+                            before.last_modified_ledger_seq
+                        }
+                        _ => sequence,
+                    };
+                    let live_until =
+                        new_live_until.or_else(|| before.as_ref().and_then(|(_, l)| *l));
+                    match &before {
+                        Some((before, _)) if *before == entry => {}
+                        Some((before, _)) => changes.extend([
+                            LedgerEntryChange::State(before.clone()),
+                            LedgerEntryChange::Updated(entry.clone()),
+                        ]),
+                        None => changes.push(LedgerEntryChange::Created(entry.clone())),
+                    }
+                    self.entries.insert(key, (entry, live_until));
+                }
+                None => {
+                    if let Some((before, _)) = self.entries.remove(&key) {
+                        changes.extend([
+                            LedgerEntryChange::State(before),
+                            LedgerEntryChange::Removed(key),
+                        ]);
+                    }
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Extend the TTL of the live entries in the read-only footprint to `extend_to` ledgers from
+    /// now. A change is reported for every entry, so `contract extend` can read the new TTL even
+    /// when it was already long enough.
+    fn extend(
+        &mut self,
+        extend_to: u32,
+        footprint: &LedgerFootprint,
+    ) -> RpcResult<Vec<LedgerEntryChange>> {
+        let sequence = self.info.sequence_number;
+        let extend_to = sequence.saturating_add(extend_to.min(self.info.max_entry_ttl - 1));
+        let mut changes = vec![];
+        for key in footprint.read_only.iter() {
+            let Some((entry, Some(live_until))) = self.entries.get_mut(key) else {
+                continue;
+            };
+            if *live_until < sequence {
+                continue;
+            }
+            let mut before = ttl_entry(key, *live_until)?;
+            before.last_modified_ledger_seq = entry.last_modified_ledger_seq;
+            *live_until = (*live_until).max(extend_to);
+            changes.extend([
+                LedgerEntryChange::State(before),
+                LedgerEntryChange::Updated(ttl_entry(key, *live_until)?),
+            ]);
+        }
+        for change in &mut changes {
+            if let LedgerEntryChange::Updated(entry) = change {
+                entry.last_modified_ledger_seq = sequence;
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Restore the archived persistent entries in the read-write footprint
+    fn restore(&mut self, footprint: &LedgerFootprint) -> RpcResult<Vec<LedgerEntryChange>> {
+        let sequence = self.info.sequence_number;
+        let restored_until = sequence + self.info.min_persistent_entry_ttl - 1;
+        let mut changes = vec![];
+        for key in footprint.read_write.iter() {
+            let Some((_, Some(live_until))) = self.entries.get_mut(key) else {
+                continue;
+            };
+            if *live_until >= sequence {
+                continue;
+            }
+            let before = ttl_entry(key, *live_until)?;
+            *live_until = restored_until;
+            let mut after = ttl_entry(key, restored_until)?;
+            after.last_modified_ledger_seq = sequence;
+            changes.extend([
+                LedgerEntryChange::State(before),
+                LedgerEntryChange::Updated(after),
+            ]);
+        }
+        Ok(changes)
+    }
+
+    /// Hash of a transaction on this network
+    fn hash(&self, tx: &Transaction) -> RpcResult<[u8; 32]> {
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(self.info.network_id),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        };
+        Ok(Sha256::digest(to_xdr(&payload)?).into())
+    }
+}
+
+/// Outcome of applying a transaction's single operation
+struct Applied {
+    success: bool,
+    result: OperationResult,
+    changes: Vec<LedgerEntryChange>,
+    return_value: ScVal,
+    events: Vec<ContractEvent>,
+    diagnostic_events: Vec<DiagnosticEvent>,
+}
+
+impl Applied {
+    fn success(result: OperationResultTr, changes: Vec<LedgerEntryChange>) -> Self {
+        Self {
+            success: true,
+            result: OperationResult::OpInner(result),
+            changes,
+            return_value: ScVal::Void,
+            events: vec![],
+            diagnostic_events: vec![],
+        }
+    }
+
+    fn failure(result: OperationResult) -> Self {
+        Self {
+            success: false,
+            result,
+            changes: vec![],
+            return_value: ScVal::Void,
+            events: vec![],
+            diagnostic_events: vec![],
+        }
+    }
+
+    fn into_result_and_meta(self) -> RpcResult<(TransactionResult, TransactionMeta)> {
+        let results = vec![self.result]
+            .try_into()
+            .map_err(|_| internal("too many operation results"))?;
+        let result = transaction_result(if self.success {
+            TransactionResultResult::TxSuccess(results)
+        } else {
+            TransactionResultResult::TxFailed(results)
+        });
+        let operations = if self.success {
+            vec![OperationMeta {
+                changes: LedgerEntryChanges(
+                    self.changes
+                        .try_into()
+                        .map_err(|_| internal("too many changes"))?,
+                ),
+            }]
+        } else {
+            vec![]
+        };
+        let meta = TransactionMeta::V3(TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: LedgerEntryChanges(VecM::default()),
+            operations: operations
+                .try_into()
+                .map_err(|_| internal("too many operations"))?,
+            tx_changes_after: LedgerEntryChanges(VecM::default()),
+            soroban_meta: Some(SorobanTransactionMeta {
+                ext: SorobanTransactionMetaExt::V0,
+                events: self
+                    .events
+                    .try_into()
+                    .map_err(|_| internal("too many events"))?,
+                return_value: self.return_value,
+                diagnostic_events: self
+                    .diagnostic_events
+                    .try_into()
+                    .map_err(|_| internal("too many diagnostic events"))?,
+            }),
+        });
+        Ok((result, meta))
+    }
+}
+
+/// Ledger state as seen by a simulation in the next ledger, which treats archived persistent
+/// entries as restored, and records them to be returned as a `restorePreamble`
+struct Simulation {
+    entries: Entries,
+    sequence: u32,
+    archived: RefCell<BTreeSet<LedgerKey>>,
+}
+
+impl SnapshotSource for Simulation {
+    fn get(
+        &self,
+        key: &Rc<LedgerKey>,
+    ) -> Result<Option<(Rc<LedgerEntry>, Option<u32>)>, HostError> {
+        let Some((entry, live_until)) = self.entries.get(key) else {
+            return Ok(None);
+        };
+        let entry = Rc::new(entry.clone());
+        match live_until {
+            Some(live_until) if *live_until < self.sequence => {
+                if is_temporary(key) {
+                    return Ok(None);
+                }
+                self.archived.borrow_mut().insert((**key).clone());
+                Ok(Some((entry, Some(self.sequence))))
+            }
+            _ => Ok(Some((entry, *live_until))),
+        }
+    }
+}
+
+fn transaction(envelope: &TransactionEnvelope) -> RpcResult<&Transaction> {
+    match envelope {
+        TransactionEnvelope::Tx(TransactionV1Envelope { tx, .. }) => Ok(tx),
+        _ => Err("only v1 transaction envelopes are supported".into()),
+    }
+}
+
+fn transaction_result(result: TransactionResultResult) -> TransactionResult {
+    TransactionResult {
+        fee_charged: 0,
+        result,
+        ext: TransactionResultExt::V0,
+    }
+}
+
+fn invoke_result(result: InvokeHostFunctionResult) -> OperationResult {
+    OperationResult::OpInner(OperationResultTr::InvokeHostFunction(result))
+}
+
+fn account_id(account: &MuxedAccount) -> AccountId {
+    let (MuxedAccount::Ed25519(key)
+    | MuxedAccount::MuxedEd25519(MuxedAccountMed25519 { ed25519: key, .. })) = account;
+    AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key.0)))
+}
+
+fn is_temporary(key: &LedgerKey) -> bool {
+    matches!(
+        key,
+        LedgerKey::ContractData(LedgerKeyContractData {
+            durability: ContractDataDurability::Temporary,
+            ..
+        })
+    )
+}
+
+fn ttl(key: &LedgerKey, live_until: u32) -> RpcResult<TtlEntry> {
+    Ok(TtlEntry {
+        key_hash: Hash(Sha256::digest(to_xdr(key)?).into()),
+        live_until_ledger_seq: live_until,
+    })
+}
+
+fn ttl_entry(key: &LedgerKey, live_until: u32) -> RpcResult<LedgerEntry> {
+    Ok(LedgerEntry {
+        last_modified_ledger_seq: 0,
+        data: LedgerEntryData::Ttl(ttl(key, live_until)?),
+        ext: LedgerEntryExt::V0,
+    })
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+fn internal(message: &str) -> RpcError {
+    RpcError::Internal(message.to_string())
+}
+
+/// Decode XDR sent by a client
+fn decode<T: ReadXdr>(encoded: &str) -> RpcResult<T> {
+    T::from_xdr_base64(encoded, Limits::none())
+        .map_err(|e| RpcError::InvalidParams(format!("invalid XDR: {e}")))
+}
+
+fn encode(value: &impl WriteXdr) -> RpcResult<String> {
+    value
+        .to_xdr_base64(Limits::none())
+        .map_err(|e| RpcError::Internal(format!("encoding XDR: {e}")))
+}
+
+fn to_xdr(value: &impl WriteXdr) -> RpcResult<Vec<u8>> {
+    value
+        .to_xdr(Limits::none())
+        .map_err(|e| RpcError::Internal(format!("encoding XDR: {e}")))
+}
+
+/// Decode values the host has already encoded
+fn from_xdr<T: ReadXdr>(bytes: &[u8]) -> RpcResult<T> {
+    T::from_xdr(bytes, Limits::none())
+        .map_err(|e| RpcError::Internal(format!("decoding XDR encoded by the host: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::{
+        ContractDataEntry, ContractExecutable, ContractIdPreimage, ContractIdPreimageFromAddress,
+        CreateContractArgs, HostFunction, InvokeContractArgs, Memo, Operation, Preconditions,
+        ScAddress, ScSymbol, ScVec, SorobanAuthorizationEntry,
+    };
+
+    const PASSPHRASE: &str = "Standalone Network ; February 2017";
+    /// The spoon loader, which writes the storage entries it is given
+    const LOADER_WASM: &[u8] = include_bytes!("../spoon/loader.wasm");
+    const SALT: [u8; 32] = [1; 32];
+
+    fn account() -> AccountId {
+        AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([7; 32])))
+    }
+
+    fn symbol(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn envelope(tx: &Transaction) -> Value {
+        json!({
+            "transaction": encode(&TransactionEnvelope::Tx(TransactionV1Envelope {
+                tx: tx.clone(),
+                signatures: VecM::default(),
+            }))
+            .unwrap()
+        })
+    }
+
+    /// Simulate and send a transaction running `function` as `account()`, and return its result
+    fn run(ledger: &mut Ledger, function: HostFunction) -> ScVal {
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: account(),
+        });
+        let LedgerEntryData::Account(source) = &ledger.entries[&key].0.data else {
+            unreachable!()
+        };
+        let mut op = InvokeHostFunctionOp {
+            host_function: function,
+            auth: VecM::default(),
+        };
+        let mut tx = Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256([7; 32])),
+            fee: 100,
+            seq_num: SequenceNumber(source.seq_num.0 + 1),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: VecM::default(),
+            ext: TransactionExt::V0,
+        };
+        tx.operations = vec![Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(op.clone()),
+        }]
+        .try_into()
+        .unwrap();
+
+        let simulation = ledger.simulate_transaction(&envelope(&tx)).unwrap();
+        assert!(simulation.get("error").is_none(), "{simulation}");
+        op.auth = simulation["results"][0]["auth"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|auth| decode::<SorobanAuthorizationEntry>(auth.as_str().unwrap()).unwrap())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        tx.operations = vec![Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(op),
+        }]
+        .try_into()
+        .unwrap();
+        tx.ext =
+            TransactionExt::V1(decode(simulation["transactionData"].as_str().unwrap()).unwrap());
+
+        let sent = ledger.send_transaction(&envelope(&tx)).unwrap();
+        let response = ledger
+            .get_transaction(&json!({ "hash": sent["hash"] }))
+            .unwrap();
+        assert_eq!(response["status"], "SUCCESS", "{response}");
+        let TransactionMeta::V3(meta) =
+            decode(response["resultMetaXdr"].as_str().unwrap()).unwrap()
+        else {
+            unreachable!()
+        };
+        meta.soroban_meta.unwrap().return_value
+    }
+
+    /// Start a ledger in `dir` with a funded account, and deploy the loader with it
+    fn deploy_loader(dir: &assert_fs::TempDir) -> (Ledger, Hash) {
+        let mut ledger = Ledger::load(dir.join("sandbox.json"), PASSPHRASE).unwrap();
+        ledger.fund(&account().to_string()).unwrap();
+        let ScVal::Bytes(hash) = run(
+            &mut ledger,
+            HostFunction::UploadContractWasm(LOADER_WASM.try_into().unwrap()),
+        ) else {
+            unreachable!()
+        };
+        let ScVal::Address(ScAddress::Contract(contract)) = run(
+            &mut ledger,
+            HostFunction::CreateContract(CreateContractArgs {
+                contract_id_preimage: ContractIdPreimage::Address(ContractIdPreimageFromAddress {
+                    address: ScAddress::Account(account()),
+                    salt: Uint256(SALT),
+                }),
+                executable: ContractExecutable::Wasm(Hash(hash.as_slice().try_into().unwrap())),
+            }),
+        ) else {
+            unreachable!()
+        };
+        (ledger, contract)
+    }
+
+    /// Store `value` under `key` with the loader, where the durability is 1 for persistent and 2
+    /// for temporary storage
+    fn store(ledger: &mut Ledger, contract: &Hash, durability: u32, key: &str, value: u32) {
+        let entry = ScVal::Vec(Some(ScVec(
+            vec![ScVal::U32(durability), symbol(key), ScVal::U32(value)]
+                .try_into()
+                .unwrap(),
+        )));
+        run(
+            ledger,
+            HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address: ScAddress::Contract(contract.clone()),
+                function_name: ScSymbol("spoon_load".try_into().unwrap()),
+                args: vec![
+                    ScVal::Address(ScAddress::Account(account())),
+                    ScVal::Bytes(SALT.to_vec().try_into().unwrap()),
+                    ScVal::Vec(Some(ScVec(vec![entry].try_into().unwrap()))),
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+        );
+    }
+
+    /// The value stored under `key` by the contract, read with `getLedgerEntries`
+    fn stored(
+        ledger: &Ledger,
+        contract: &Hash,
+        durability: ContractDataDurability,
+        key: &str,
+    ) -> Option<ScVal> {
+        let key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(contract.clone()),
+            key: symbol(key),
+            durability,
+        });
+        let response = ledger
+            .get_ledger_entries(&json!({ "keys": [encode(&key).unwrap()] }))
+            .unwrap();
+        let entry = response["entries"].as_array().unwrap().first()?;
+        let LedgerEntryData::ContractData(ContractDataEntry { val, .. }) =
+            decode(entry["xdr"].as_str().unwrap()).unwrap()
+        else {
+            unreachable!()
+        };
+        Some(val)
+    }
+
+    #[test]
+    fn state_survives_restarts() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let (mut ledger, contract) = deploy_loader(&dir);
+        store(&mut ledger, &contract, 1, "COUNTER", 7);
+        let sequence = ledger.info.sequence_number;
+        drop(ledger);
+
+        let ledger = Ledger::load(dir.join("sandbox.json"), PASSPHRASE).unwrap();
+        assert_eq!(ledger.info.sequence_number, sequence);
+        assert_eq!(
+            stored(
+                &ledger,
+                &contract,
+                ContractDataDurability::Persistent,
+                "COUNTER"
+            ),
+            Some(ScVal::U32(7))
+        );
+        assert!(matches!(
+            Ledger::load(
+                dir.join("sandbox.json"),
+                "Test SDF Network ; September 2015"
+            ),
+            Err(Error::NetworkMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn time_travel_expires_temporary_entries() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let (mut ledger, contract) = deploy_loader(&dir);
+        store(&mut ledger, &contract, 2, "SESSION", 1);
+        store(&mut ledger, &contract, 1, "COUNTER", 7);
+        assert_eq!(
+            stored(
+                &ledger,
+                &contract,
+                ContractDataDurability::Temporary,
+                "SESSION"
+            ),
+            Some(ScVal::U32(1))
+        );
+
+        let min_temp_entry_ttl = ledger.info.min_temp_entry_ttl;
+        ledger
+            .time_travel(&json!({ "ledgers": min_temp_entry_ttl }))
+            .unwrap();
+        assert_eq!(
+            stored(
+                &ledger,
+                &contract,
+                ContractDataDurability::Temporary,
+                "SESSION"
+            ),
+            None
+        );
+        assert_eq!(
+            stored(
+                &ledger,
+                &contract,
+                ContractDataDurability::Persistent,
+                "COUNTER"
+            ),
+            Some(ScVal::U32(7))
+        );
+    }
+
+    #[test]
+    fn invalid_params() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let mut ledger = Ledger::load(dir.join("sandbox.json"), PASSPHRASE).unwrap();
+        for error in [
+            ledger.time_travel(&json!({})).unwrap_err(),
+            ledger
+                .send_transaction(&json!({ "transaction": "not XDR" }))
+                .unwrap_err(),
+            ledger
+                .get_ledger_entries(&json!({ "keys": [1] }))
+                .unwrap_err(),
+        ] {
+            assert_eq!(error.code(), -32602, "{error}");
+        }
+    }
+}
@@ -0,0 +1,302 @@
+//! In-process sandbox network, started for environments whose network sets `run-locally = true`.
+//!
+//! It serves the parts of the Soroban RPC API used by Loam, the Stellar CLI and the JS SDK
+//! (`getHealth`, `getNetwork`, `getLatestLedger`, `getLedgerEntries`, `simulateTransaction`,
+//! `sendTransaction` and `getTransaction`), and a friendbot at `/friendbot?addr=<G...>`.
+//! Each transaction is applied by the Soroban host in a ledger of its own, without checking
+//! signatures or charging fees, which `getNetwork` reports in its `sandbox` field. The ledger is saved to `target/loam/sandbox.json` so its state
+//! survives restarts. The non-standard `loam_timeTravel` method closes `ledgers` empty ledgers, to
+//! move time forward past TTLs and time locks.
+
+mod ledger;
+
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{tcp::OwnedWriteHalf, TcpListener, TcpStream};
+
+use ledger::{Ledger, RpcError};
+
+/// Where the sandbox listens unless the network sets `rpc-url`, the same as a local quickstart
+pub const DEFAULT_RPC_URL: &str = "http://localhost:8000/rpc";
+
+/// Largest request body the sandbox reads, well above the largest transaction, which uploads a
+/// contract of at most 64 KiB
+const MAX_CONTENT_LENGTH: usize = 1 << 20;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Snapshot(#[from] soroban_ledger_snapshot::Error),
+    #[error("⛔ ️`run-locally` needs an `rpc-url` like \"http://localhost:8000/rpc\", got {0:?}")]
+    InvalidRpcUrl(String),
+    #[error("⛔ ️starting the local network at {address}: {error}")]
+    Bind {
+        address: String,
+        error: std::io::Error,
+    },
+    #[error("⛔ ️{0:?} holds a ledger for another network passphrase; delete it to start over")]
+    NetworkMismatch(PathBuf),
+}
+
+/// RPC URL of the sandbox running in this process, so `loam dev` only starts it once
+static RUNNING: OnceLock<String> = OnceLock::new();
+
+struct Sandbox {
+    ledger: Mutex<Ledger>,
+    friendbot_url: String,
+}
+
+/// Start the sandbox at `rpc_url`, with the ledger in `target/loam/sandbox.json`, unless it is
+/// already running in this process
+pub fn start(workspace_root: &Path, rpc_url: &str, passphrase: &str) -> Result<(), Error> {
+    if RUNNING.get().is_some() {
+        return Ok(());
+    }
+    let address = rpc_url
+        .strip_prefix("http://")
+        .and_then(|rest| rest.split('/').next())
+        .filter(|address| address.contains(':'))
+        .ok_or_else(|| Error::InvalidRpcUrl(rpc_url.to_string()))?;
+    let path = workspace_root.join("target/loam/sandbox.json");
+    let ledger = Ledger::load(path.clone(), passphrase)?;
+
+    let listener = std::net::TcpListener::bind(address).map_err(|error| Error::Bind {
+        address: address.to_string(),
+        error,
+    })?;
+    listener.set_nonblocking(true)?;
+    let listener = TcpListener::from_std(listener)?;
+    let sandbox = Arc::new(Sandbox {
+        ledger: Mutex::new(ledger),
+        friendbot_url: format!("http://{address}/friendbot"),
+    });
+    tokio::spawn(serve(listener, sandbox));
+    RUNNING.get_or_init(|| rpc_url.to_string());
+    eprintln!(
+        "🏝️ running local network at {rpc_url}, with its ledger in {}; it does not check \
+         signatures or charge fees",
+        path.display()
+    );
+    Ok(())
+}
+
+async fn serve(listener: TcpListener, sandbox: Arc<Sandbox>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                // Errors such as running out of file descriptors persist for a while, so wait
+                // before accepting again rather than spinning
+                eprintln!("⚠️ local network failed to accept a connection: {e}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        // Connections closed by clients mid-request are not worth reporting
+        tokio::spawn(connection(sandbox.clone(), stream));
+    }
+}
+
+/// Answer HTTP requests on a kept-alive connection until the client closes it
+async fn connection(sandbox: Arc<Sandbox>, stream: TcpStream) -> std::io::Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut read = BufReader::new(read);
+    loop {
+        let (method, target, body) = match read_request(&mut read).await {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                let detail = json!({ "detail": e.to_string() });
+                return write_response(&mut write, "413 Payload Too Large", &detail).await;
+            }
+            Err(e) => return Err(e),
+        };
+        // Applying a transaction runs the Soroban host, so keep it off the async workers
+        let sandbox = sandbox.clone();
+        let (status, response) =
+            tokio::task::spawn_blocking(move || sandbox.respond(&method, &target, &body))
+                .await
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        write_response(&mut write, status, &response).await?;
+    }
+}
+
+async fn write_response(
+    write: &mut OwnedWriteHalf,
+    status: &str,
+    response: &Value,
+) -> std::io::Result<()> {
+    let response = response.to_string();
+    write
+        .write_all(
+            format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{response}",
+                response.len()
+            )
+            .as_bytes(),
+        )
+        .await
+}
+
+impl Sandbox {
+    fn respond(&self, method: &str, target: &str, body: &[u8]) -> (&'static str, Value) {
+        // A request that panicked shouldn't take the whole network down, so keep serving the
+        // ledger it left behind
+        let mut ledger = self.ledger.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(query) = target.strip_prefix("/friendbot?") {
+            let address = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("addr="))
+                .unwrap_or_default();
+            return match ledger.fund(address) {
+                Ok(response) => ("200 OK", response),
+                Err(RpcError::InvalidParams(detail)) => (
+                    "400 Bad Request",
+                    json!({ "status": 400, "detail": detail }),
+                ),
+                Err(RpcError::Internal(detail)) => (
+                    "500 Internal Server Error",
+                    json!({ "status": 500, "detail": detail }),
+                ),
+            };
+        }
+        if method != "POST" {
+            return ("404 Not Found", json!({ "detail": "not found" }));
+        }
+        let response = match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(requests)) => requests
+                .iter()
+                .map(|request| rpc(&mut ledger, &self.friendbot_url, request))
+                .collect(),
+            Ok(request) => rpc(&mut ledger, &self.friendbot_url, &request),
+            Err(e) => rpc_error(&Value::Null, -32700, e.to_string()),
+        };
+        ("200 OK", response)
+    }
+}
+
+/// Answer a JSON-RPC request
+fn rpc(ledger: &mut Ledger, friendbot_url: &str, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let method = request
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let result = match method {
+        "getHealth" => Ok(ledger.get_health()),
+        "getNetwork" => Ok(ledger.get_network(friendbot_url)),
+        "getLatestLedger" => Ok(ledger.get_latest_ledger()),
+        "getLedgerEntries" => ledger.get_ledger_entries(&params),
+        "getTransaction" => ledger.get_transaction(&params),
+        "simulateTransaction" => ledger.simulate_transaction(&params),
+        "sendTransaction" => ledger.send_transaction(&params),
+        "loam_timeTravel" => ledger.time_travel(&params),
+        _ => return rpc_error(&id, -32601, format!("method not found: {method:?}")),
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => rpc_error(&id, e.code(), e.to_string()),
+    }
+}
+
+fn rpc_error(id: &Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Read the method, target and body of the next HTTP request, if the connection is still open
+async fn read_request(
+    read: &mut (impl AsyncBufRead + Unpin),
+) -> std::io::Result<Option<(String, String, Vec<u8>)>> {
+    let mut line = String::new();
+    if read.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    let mut request_line = line.split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if read.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or_default();
+            }
+        }
+    }
+    if content_length > MAX_CONTENT_LENGTH {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("request bodies are limited to {MAX_CONTENT_LENGTH} bytes"),
+        ));
+    }
+    let mut body = vec![0; content_length];
+    read.read_exact(&mut body).await?;
+    Ok(Some((method, target, body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_requests() {
+        let mut read: &[u8] =
+            b"POST /rpc HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}GET /friendbot?addr=G HTTP/1.1\r\n\r\n";
+        assert_eq!(
+            read_request(&mut read).await.unwrap(),
+            Some(("POST".to_string(), "/rpc".to_string(), b"{}".to_vec()))
+        );
+        assert_eq!(
+            read_request(&mut read).await.unwrap(),
+            Some(("GET".to_string(), "/friendbot?addr=G".to_string(), vec![]))
+        );
+        assert_eq!(read_request(&mut read).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn read_request_too_large() {
+        let request = format!(
+            "POST /rpc HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_CONTENT_LENGTH + 1
+        );
+        let error = read_request(&mut request.as_bytes()).await.unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn respond_after_a_panic() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let ledger = Ledger::load(dir.path().join("sandbox.json"), "Test SDF Network").unwrap();
+        let sandbox = Arc::new(Sandbox {
+            ledger: Mutex::new(ledger),
+            friendbot_url: String::new(),
+        });
+        let poisoned = sandbox.clone();
+        std::thread::spawn(move || {
+            let _ledger = poisoned.ledger.lock().unwrap();
+            panic!("request panicked");
+        })
+        .join()
+        .unwrap_err();
+        assert!(sandbox.ledger.is_poisoned());
+
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "getHealth" });
+        let (status, response) = sandbox.respond("POST", "/rpc", request.to_string().as_bytes());
+        assert_eq!(status, "200 OK");
+        assert_eq!(response["result"]["status"], "healthy");
+    }
+}
@@ -0,0 +1,68 @@
+//! Contract that `loam build` deploys to recreate a spooned contract: it writes the spooned
+//! storage entries under its own address, then replaces itself with the spooned contract's Wasm.
+//!
+//! This is not part of loam-cli, which embeds it prebuilt as `loader.wasm`. Rebuild that with
+//! `just build-spoon-loader` after changing it.
+//!
+//! Only the account that deployed the loader can call it. Contracts cannot store anything as they
+//! are deployed yet, so each call passes the deployer and the salt it deployed with, and the
+//! loader checks that its own address derives from them.
+#![no_std]
+use soroban_sdk::{contract, contracterror, contractimpl, Address, BytesN, Env, Val, Vec};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Error {
+    /// The loader was not deployed by this address with this salt
+    NotDeployer = 1,
+}
+
+#[contract]
+pub struct SpoonLoader;
+
+/// Require the auth of `deployer`, and check that it deployed this contract with `salt`
+fn check_deployer(env: &Env, deployer: Address, salt: BytesN<32>) -> Result<(), Error> {
+    deployer.require_auth();
+    if env.deployer().with_address(deployer, salt).deployed_address()
+        == env.current_contract_address()
+    {
+        Ok(())
+    } else {
+        Err(Error::NotDeployer)
+    }
+}
+
+#[contractimpl]
+impl SpoonLoader {
+    /// Write storage entries as `(durability, key, value)`, where the durability is 0 for
+    /// instance, 1 for persistent and 2 for temporary storage
+    pub fn spoon_load(
+        env: Env,
+        deployer: Address,
+        salt: BytesN<32>,
+        entries: Vec<(u32, Val, Val)>,
+    ) -> Result<(), Error> {
+        check_deployer(&env, deployer, salt)?;
+        for (durability, key, value) in entries {
+            match durability {
+                0 => env.storage().instance().set(&key, &value),
+                1 => env.storage().persistent().set(&key, &value),
+                _ => env.storage().temporary().set(&key, &value),
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace this contract with the spooned contract's Wasm
+    pub fn spoon_finish(
+        env: Env,
+        deployer: Address,
+        salt: BytesN<32>,
+        wasm_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        check_deployer(&env, deployer, salt)?;
+        env.deployer().update_current_contract_wasm(wasm_hash);
+        Ok(())
+    }
+}
@@ -0,0 +1,556 @@
+//! Spoon a contract's Wasm and storage from another network, or a ledger snapshot, into the
+//! current environment's network.
+//!
+//! Storage can only be written by the contract that owns it, so spooned contracts are recreated by
+//! deploying a loader contract, writing the spooned entries with it, and then replacing it with
+//! the spooned contract's Wasm.
+use rand::Rng;
+use serde_json::json;
+use soroban_cli::commands as cli;
+use soroban_cli::commands::NetworkRunnable;
+use soroban_cli::CommandParser;
+use std::path::Path;
+use stellar_xdr::curr::{
+    ContractCodeEntry, ContractDataDurability, ContractDataEntry, ContractExecutable, Hash,
+    LedgerEntryData, LedgerKey, LedgerKeyContractCode, LedgerKeyContractData, Limits, ReadXdr,
+    ScAddress, ScContractInstance, ScVal,
+};
+
+use super::env_toml;
+
+/// The loader contract, built from `loader.rs` with `just build-spoon-loader`
+const LOADER_WASM: &[u8] = include_bytes!("loader.wasm");
+
+/// Number of entries written per `spoon_load` transaction
+const ENTRIES_PER_TX: usize = 10;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    EnvironmentsToml(#[from] env_toml::Error),
+    #[error("⛔ ️spooned contract {0:?} needs an `environment` or `snapshot` to spoon from")]
+    NoSource(String),
+    #[error("⛔ ️no settings for environment {0:?} to spoon from found in environments.toml")]
+    NoSourceEnvironment(String),
+    #[error("⛔ ️invalid network: must either specify a network name or both network_passphrase and rpc_url")]
+    MalformedNetwork,
+    #[error("⛔ ️invalid contract ID {0:?}")]
+    InvalidContractId(String),
+    #[error("⛔ ️invalid storage key {0:?}: {1}")]
+    InvalidKey(String, stellar_xdr::curr::Error),
+    #[error("⛔ ️contract {0:?} not found")]
+    ContractNotFound(String),
+    #[error("⛔ ️only contracts with Wasm can be spooned, {0:?} is a Stellar Asset Contract")]
+    NotWasm(String),
+    #[error("⛔ ️Wasm of contract {0:?} not found")]
+    WasmNotFound(String),
+    #[error("⛔ ️no account to spoon with; set STELLAR_ACCOUNT or make one of the environment's accounts the default")]
+    MissingSourceAccount,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Rpc(#[from] soroban_rpc::Error),
+    #[error(transparent)]
+    Network(#[from] cli::network::Error),
+    #[error(transparent)]
+    ContractInstall(#[from] cli::contract::install::Error),
+    #[error(transparent)]
+    ContractDeploy(#[from] cli::contract::deploy::wasm::Error),
+    #[error(transparent)]
+    ContractInvoke(#[from] cli::contract::invoke::Error),
+    #[error(transparent)]
+    Address(#[from] cli::keys::address::Error),
+    #[error(transparent)]
+    Clap(#[from] clap::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Storage durability as understood by the loader's `spoon_load`
+const INSTANCE: u32 = 0;
+const PERSISTENT: u32 = 1;
+const TEMPORARY: u32 = 2;
+
+/// A contract's Wasm and storage, as fetched from the network or snapshot it is spooned from
+pub struct Spooned {
+    pub wasm: Vec<u8>,
+    /// Storage entries as `(durability, key, value)`
+    pub entries: Vec<(u32, ScVal, ScVal)>,
+}
+
+/// Fetch the latest Wasm and storage of the contract at `address`
+pub async fn fetch(
+    workspace_root: &Path,
+    name: &str,
+    address: &str,
+    settings: &env_toml::Contract,
+) -> Result<Spooned, Error> {
+    let contract = soroban_cli::utils::contract_id_from_str(address)
+        .map_err(|_| Error::InvalidContractId(address.to_string()))?;
+    let contract = ScAddress::Contract(Hash(contract));
+
+    let ledger_entries = if let Some(snapshot) = &settings.snapshot {
+        eprintln!("🥄 spooning {name:?} from {}", snapshot.display());
+        snapshot_entries(&workspace_root.join(snapshot), address, &contract)?
+    } else if let Some(environment) = &settings.environment {
+        eprintln!("🥄 spooning {name:?} from the {environment:?} environment");
+        let rpc_url = rpc_url(workspace_root, environment)?;
+        rpc_entries(&rpc_url, address, &contract, &settings.keys).await?
+    } else {
+        return Err(Error::NoSource(name.to_string()));
+    };
+
+    let mut wasm = None;
+    let mut entries = Vec::new();
+    for data in ledger_entries {
+        match data {
+            LedgerEntryData::ContractCode(ContractCodeEntry { code, .. }) => {
+                wasm = Some(code.to_vec());
+            }
+            LedgerEntryData::ContractData(ContractDataEntry {
+                key: ScVal::LedgerKeyContractInstance,
+                val: ScVal::ContractInstance(ScContractInstance { storage, .. }),
+                ..
+            }) => entries.extend(
+                storage
+                    .into_iter()
+                    .flat_map(|storage| storage.0.into_vec())
+                    .map(|entry| (INSTANCE, entry.key, entry.val)),
+            ),
+            // Nonces belong to the host, and cannot be written by contracts
+            LedgerEntryData::ContractData(ContractDataEntry {
+                key: ScVal::LedgerKeyNonce(_),
+                ..
+            }) => {}
+            LedgerEntryData::ContractData(ContractDataEntry {
+                key,
+                val,
+                durability,
+                ..
+            }) => {
+                let durability = match durability {
+                    ContractDataDurability::Persistent => PERSISTENT,
+                    ContractDataDurability::Temporary => TEMPORARY,
+                };
+                entries.push((durability, key, val));
+            }
+            _ => {}
+        }
+    }
+    let wasm = wasm.ok_or_else(|| Error::WasmNotFound(address.to_string()))?;
+    Ok(Spooned { wasm, entries })
+}
+
+/// Recreate the spooned contract's storage with a new loader contract, then upgrade it to
+/// `wasm_hash`, which must already be installed. Returns the new contract's ID.
+pub async fn recreate(
+    workspace_root: &Path,
+    name: &str,
+    entries: &[(u32, ScVal, ScVal)],
+    wasm_hash: &str,
+) -> Result<String, Error> {
+    let account = std::env::var("STELLAR_ACCOUNT").map_err(|_| Error::MissingSourceAccount)?;
+    let deployer = cli::keys::address::Cmd::parse_arg_vec(&[&account])?
+        .public_key()?
+        .to_string();
+    // The loader only lets the account that deployed it with this salt call it
+    let salt = hex::encode(rand::thread_rng().gen::<[u8; 32]>());
+
+    let loader = workspace_root.join("target/loam/spoon-loader.wasm");
+    std::fs::create_dir_all(workspace_root.join("target/loam"))?;
+    std::fs::write(&loader, LOADER_WASM)?;
+    let loader_hash = cli::contract::install::Cmd::parse_arg_vec(&[
+        "--source-account",
+        &account,
+        "--wasm",
+        loader.to_str().expect("we do not support non-utf8 paths"),
+    ])?
+    .run_against_rpc_server(None, None)
+    .await?
+    .into_result()
+    .expect("no hash returned by 'contract install'")
+    .to_string();
+    let contract_id = cli::contract::deploy::wasm::Cmd::parse_arg_vec(&[
+        "--source-account",
+        &account,
+        "--wasm-hash",
+        &loader_hash,
+        "--salt",
+        &salt,
+    ])?
+    .run_against_rpc_server(None, None)
+    .await?
+    .into_result()
+    .expect("no contract id returned by 'contract deploy'");
+
+    eprintln!("🥄 writing {} storage entries for {name:?}", entries.len());
+    for batch in entries.chunks(ENTRIES_PER_TX) {
+        let batch = batch
+            .iter()
+            .map(|(durability, key, val)| {
+                Ok(json!([
+                    durability,
+                    serde_json::to_value(key)?,
+                    serde_json::to_value(val)?
+                ]))
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        cli::contract::invoke::Cmd::parse_arg_vec(&[
+            "--source-account",
+            &account,
+            "--id",
+            &contract_id,
+            "--",
+            "spoon_load",
+            "--deployer",
+            &deployer,
+            "--salt",
+            &salt,
+            "--entries",
+            &serde_json::to_string(&batch)?,
+        ])?
+        .run_against_rpc_server(None, None)
+        .await?;
+    }
+    cli::contract::invoke::Cmd::parse_arg_vec(&[
+        "--source-account",
+        &account,
+        "--id",
+        &contract_id,
+        "--",
+        "spoon_finish",
+        "--deployer",
+        &deployer,
+        "--salt",
+        &salt,
+        "--wasm_hash",
+        wasm_hash,
+    ])?
+    .run_against_rpc_server(None, None)
+    .await?;
+    Ok(contract_id)
+}
+
+fn snapshot_entries(
+    snapshot: &Path,
+    address: &str,
+    contract: &ScAddress,
+) -> Result<Vec<LedgerEntryData>, Error> {
+    let mut snapshot: serde_json::Value = serde_json::from_reader(std::fs::File::open(snapshot)?)?;
+    // Test snapshots written by the Soroban SDK wrap the ledger snapshot with the test's events
+    if let Some(ledger) = snapshot.get_mut("ledger") {
+        snapshot = ledger.take();
+    }
+    let snapshot: soroban_ledger_snapshot::LedgerSnapshot = serde_json::from_value(snapshot)?;
+    let is_contract_data = |key: &LedgerKey| matches!(key, LedgerKey::ContractData(LedgerKeyContractData { contract: c, .. }) if c == contract);
+    let mut found: Vec<_> = snapshot
+        .ledger_entries
+        .iter()
+        .filter(|(key, _)| is_contract_data(key))
+        .map(|(_, (entry, _))| entry.data.clone())
+        .collect();
+    let code = LedgerKey::ContractCode(LedgerKeyContractCode {
+        hash: wasm_hash(&found, address)?,
+    });
+    found.extend(
+        snapshot
+            .ledger_entries
+            .iter()
+            .filter(|(key, _)| key.as_ref() == &code)
+            .map(|(_, (entry, _))| entry.data.clone()),
+    );
+    Ok(found)
+}
+
+async fn rpc_entries(
+    rpc_url: &str,
+    address: &str,
+    contract: &ScAddress,
+    keys: &[String],
+) -> Result<Vec<LedgerEntryData>, Error> {
+    let client = soroban_rpc::Client::new(rpc_url)?;
+    let data_key = |key: ScVal, durability| {
+        LedgerKey::ContractData(LedgerKeyContractData {
+            contract: contract.clone(),
+            key,
+            durability,
+        })
+    };
+    let mut ledger_keys = vec![data_key(
+        ScVal::LedgerKeyContractInstance,
+        ContractDataDurability::Persistent,
+    )];
+    for key in keys {
+        let key = ScVal::from_xdr_base64(key, Limits::none())
+            .map_err(|e| Error::InvalidKey(key.clone(), e))?;
+        // The entry can be either, so look for both
+        ledger_keys.push(data_key(key.clone(), ContractDataDurability::Persistent));
+        ledger_keys.push(data_key(key, ContractDataDurability::Temporary));
+    }
+    let mut found: Vec<_> = client
+        .get_full_ledger_entries(&ledger_keys)
+        .await?
+        .entries
+        .into_iter()
+        .map(|entry| entry.val)
+        .collect();
+    let code = LedgerKey::ContractCode(LedgerKeyContractCode {
+        hash: wasm_hash(&found, address)?,
+    });
+    found.extend(
+        client
+            .get_full_ledger_entries(&[code])
+            .await?
+            .entries
+            .into_iter()
+            .map(|entry| entry.val),
+    );
+    Ok(found)
+}
+
+/// Hash of the Wasm of the contract whose instance is among `entries`
+fn wasm_hash(entries: &[LedgerEntryData], address: &str) -> Result<Hash, Error> {
+    let executable = entries.iter().find_map(|data| match data {
+        LedgerEntryData::ContractData(ContractDataEntry {
+            val: ScVal::ContractInstance(instance),
+            ..
+        }) => Some(instance.executable.clone()),
+        _ => None,
+    });
+    match executable {
+        Some(ContractExecutable::Wasm(hash)) => Ok(hash),
+        Some(ContractExecutable::StellarAsset) => Err(Error::NotWasm(address.to_string())),
+        None => Err(Error::ContractNotFound(address.to_string())),
+    }
+}
+
+/// RPC URL of the network of the environment named `environment` in environments.toml
+fn rpc_url(workspace_root: &Path, environment: &str) -> Result<String, Error> {
+    let network = env_toml::Environment::get(workspace_root, environment)?
+        .ok_or_else(|| Error::NoSourceEnvironment(environment.to_string()))?
+        .network;
+    match network {
+        env_toml::Network {
+            name: Some(name), ..
+        } => Ok(cli::network::Args {
+            network: Some(name),
+            rpc_url: None,
+            network_passphrase: None,
+        }
+        .get(&cli::config::locator::Args {
+            global: false,
+            config_dir: None,
+        })?
+        .rpc_url),
+        env_toml::Network {
+            rpc_url: Some(rpc_url),
+            ..
+        } => Ok(rpc_url),
+        _ => Err(Error::MalformedNetwork),
+    }
+}
+
+/// Whether spooning into `network` only affects the developer's own machine: the network is
+/// standalone, or its RPC server runs on localhost
+pub fn is_local(network: &cli::network::Network) -> bool {
+    if network.network_passphrase == cli::network::LOCAL_NETWORK_PASSPHRASE {
+        return true;
+    }
+    let url = network.rpc_url.as_str();
+    let authority = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_ledger_snapshot::LedgerSnapshot;
+    use stellar_xdr::curr::{
+        ContractCodeEntryExt, ExtensionPoint, LedgerEntry, LedgerEntryExt, ScMap, ScMapEntry,
+        ScNonceKey, ScSymbol,
+    };
+
+    const ADDRESS: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    fn symbol(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn entry(data: LedgerEntryData) -> (Box<LedgerKey>, (Box<LedgerEntry>, Option<u32>)) {
+        let key = match &data {
+            LedgerEntryData::ContractData(data) => LedgerKey::ContractData(LedgerKeyContractData {
+                contract: data.contract.clone(),
+                key: data.key.clone(),
+                durability: data.durability,
+            }),
+            LedgerEntryData::ContractCode(code) => LedgerKey::ContractCode(LedgerKeyContractCode {
+                hash: code.hash.clone(),
+            }),
+            _ => unreachable!(),
+        };
+        let entry = LedgerEntry {
+            last_modified_ledger_seq: 1,
+            data,
+            ext: LedgerEntryExt::V0,
+        };
+        (Box::new(key), (Box::new(entry), Some(100)))
+    }
+
+    fn data(key: ScVal, durability: ContractDataDurability, val: ScVal) -> LedgerEntryData {
+        LedgerEntryData::ContractData(ContractDataEntry {
+            ext: ExtensionPoint::V0,
+            contract: ScAddress::Contract(Hash([1; 32])),
+            key,
+            durability,
+            val,
+        })
+    }
+
+    fn instance(executable: ContractExecutable) -> LedgerEntryData {
+        let storage = ScMap(
+            vec![ScMapEntry {
+                key: symbol("COUNTER"),
+                val: ScVal::U32(41),
+            }]
+            .try_into()
+            .unwrap(),
+        );
+        data(
+            ScVal::LedgerKeyContractInstance,
+            ContractDataDurability::Persistent,
+            ScVal::ContractInstance(ScContractInstance {
+                executable,
+                storage: Some(storage),
+            }),
+        )
+    }
+
+    /// Write a snapshot with `entries` to a new directory, with a contract at `ADDRESS` that spoons
+    /// from it, and fetch the contract
+    async fn fetch_from(entries: Vec<LedgerEntryData>) -> Result<Spooned, Error> {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let snapshot = LedgerSnapshot {
+            ledger_entries: entries.into_iter().map(entry).collect(),
+            ..LedgerSnapshot::default()
+        };
+        snapshot.write_file(dir.join("snapshot.json")).unwrap();
+        let settings: env_toml::Contract = toml::from_str(&format!(
+            "address = {ADDRESS:?}\nsnapshot = \"snapshot.json\""
+        ))
+        .unwrap();
+        fetch(&dir, "counter", ADDRESS, &settings).await
+    }
+
+    #[tokio::test]
+    async fn fetch_from_snapshot() {
+        let spooned = fetch_from(vec![
+            instance(ContractExecutable::Wasm(Hash([2; 32]))),
+            data(
+                symbol("Balance"),
+                ContractDataDurability::Persistent,
+                ScVal::U64(7),
+            ),
+            data(
+                symbol("Allowance"),
+                ContractDataDurability::Temporary,
+                ScVal::U64(3),
+            ),
+            data(
+                ScVal::LedgerKeyNonce(ScNonceKey { nonce: 1 }),
+                ContractDataDurability::Temporary,
+                ScVal::Void,
+            ),
+            LedgerEntryData::ContractCode(ContractCodeEntry {
+                ext: ContractCodeEntryExt::V0,
+                hash: Hash([2; 32]),
+                code: b"\0asm".to_vec().try_into().unwrap(),
+            }),
+            // Wasm that the contract does not run
+            LedgerEntryData::ContractCode(ContractCodeEntry {
+                ext: ContractCodeEntryExt::V0,
+                hash: Hash([3; 32]),
+                code: b"other".to_vec().try_into().unwrap(),
+            }),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(spooned.wasm, b"\0asm");
+        assert_eq!(
+            spooned.entries,
+            vec![
+                (INSTANCE, symbol("COUNTER"), ScVal::U32(41)),
+                (PERSISTENT, symbol("Balance"), ScVal::U64(7)),
+                (TEMPORARY, symbol("Allowance"), ScVal::U64(3)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_missing_contract() {
+        let error = fetch_from(vec![]).await.err().unwrap();
+        assert!(matches!(error, Error::ContractNotFound(address) if address == ADDRESS));
+    }
+
+    #[tokio::test]
+    async fn fetch_missing_wasm() {
+        let error = fetch_from(vec![instance(ContractExecutable::Wasm(Hash([2; 32])))])
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::WasmNotFound(address) if address == ADDRESS));
+    }
+
+    #[tokio::test]
+    async fn fetch_stellar_asset_contract() {
+        let error = fetch_from(vec![instance(ContractExecutable::StellarAsset)])
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::NotWasm(address) if address == ADDRESS));
+    }
+
+    #[test]
+    fn local_networks() {
+        let network = |rpc_url: &str, network_passphrase: &str| cli::network::Network {
+            rpc_url: rpc_url.to_string(),
+            network_passphrase: network_passphrase.to_string(),
+        };
+        let testnet = "Test SDF Network ; September 2015";
+        assert!(is_local(&network(
+            "https://rpc.example.org",
+            cli::network::LOCAL_NETWORK_PASSPHRASE
+        )));
+        for rpc_url in [
+            "http://localhost:8000/rpc",
+            "http://LOCALHOST/rpc",
+            "http://127.0.0.1:8000",
+            "http://[::1]:8000/rpc",
+            "http://user@localhost:8000",
+        ] {
+            assert!(is_local(&network(rpc_url, testnet)), "{rpc_url}");
+        }
+        for rpc_url in [
+            "https://soroban-testnet.stellar.org",
+            "https://localhost.example.org/rpc",
+            "http://10.0.0.1:8000",
+            "https://rpc.example.org/?host=localhost",
+        ] {
+            assert!(!is_local(&network(rpc_url, testnet)), "{rpc_url}");
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use clap::Parser;
+use soroban_cli::commands as cli;
+use soroban_cli::commands::NetworkRunnable;
+use soroban_spec_tools::Spec;
+use std::collections::BTreeMap as Map;
+use std::path::PathBuf;
+use std::str::FromStr;
+use stellar_xdr::curr::{ScSpecTypeDef, ScVal};
+
+use crate::commands::build::{
+    self,
+    clients::{self, DEFAULT_ENV},
+    env_toml,
+};
+use crate::commands::publish;
+use crate::CommandParser;
+
+/// Deploy a published contract through the Contract Registry
+///
+/// Deploys a release from the Package Manager under a unique name with the Contract Registry set as
+/// `registry` in the network settings of the current `LOAM_ENV` in `environments.toml`. Pass an
+/// initialization function and its arguments after `--` to call it on the new contract in the same
+/// transaction, so the contract cannot be front-run:
+///
+/// loam deploy hello-world@0.1.0 --name hello -- init --admin alice
+///
+/// The new contract ID is saved as a local alias under its name.
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Published package to deploy, as `<package>@<version>`
+    ///
+    /// If the version is omitted, the latest release is deployed.
+    pub package: PackageVersion,
+    /// Name to claim in the registry, also used as the local contract alias
+    #[arg(long)]
+    pub name: String,
+    /// Account that owns the name
+    ///
+    /// If omitted, the source account is used.
+    #[arg(long)]
+    pub owner: Option<String>,
+    /// Path to Cargo.toml
+    #[arg(long, default_value = "Cargo.toml")]
+    pub manifest_path: PathBuf,
+    #[command(flatten)]
+    pub env: clients::Args,
+    /// Initialization function to call on the new contract, followed by its arguments
+    #[arg(last = true, id = "INIT_FN_AND_ARGS")]
+    pub init: Vec<String>,
+}
+
+/// A package name with an optional version, parsed from `<package>@<version>`
+#[derive(Debug, Clone)]
+pub struct PackageVersion {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl FromStr for PackageVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, version) = match s.split_once('@') {
+            Some((name, version)) => (name, Some(version.to_string())),
+            None => (s, None),
+        };
+        if name.is_empty() || version.as_ref().is_some_and(String::is_empty) {
+            return Err(format!("expected <package>@<version>, got {s:?}"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            version,
+        })
+    }
+}
+
+impl std::fmt::Display for PackageVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}@{version}", self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Clients(#[from] clients::Error),
+    #[error(transparent)]
+    EnvironmentsToml(#[from] env_toml::Error),
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(transparent)]
+    Publish(#[from] publish::Error),
+    #[error(
+        "⛔ ️no environments.toml found; it sets the network and Contract Registry to deploy with"
+    )]
+    NoEnvironmentsToml,
+    #[error("⛔ ️no `registry` contract set for the network in environments.toml")]
+    NoRegistry,
+    #[error("⛔ ️{0} has not been published")]
+    NotPublished(String),
+    #[error(
+        "⛔ ️no account to deploy as; pass --owner, set STELLAR_ACCOUNT or make one of the environment's accounts the default"
+    )]
+    MissingSourceAccount,
+    #[error("⛔ ️initialization arguments must be passed as `--<name> <value>`, got {0:?}")]
+    MalformedInitArg(String),
+    #[error("⛔ ️{function:?} takes no argument named {arg:?}")]
+    UnknownInitArg { function: String, arg: String },
+    #[error("⛔ ️missing argument {arg:?} for {function:?}")]
+    MissingInitArg { function: String, arg: String },
+    #[error("⛔ ️parsing argument {arg:?}: {error}")]
+    ParsingInitArg {
+        arg: String,
+        error: soroban_spec_tools::Error,
+    },
+    #[error(transparent)]
+    Spec(#[from] soroban_spec_tools::Error),
+    #[error(transparent)]
+    Rpc(#[from] soroban_rpc::Error),
+    #[error(transparent)]
+    ConfigLocator(#[from] cli::config::locator::Error),
+    #[error(transparent)]
+    ContractInvoke(#[from] cli::contract::invoke::Error),
+    #[error(transparent)]
+    Clap(#[from] clap::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let metadata = build::Cmd::parse_arg_vec(&[
+            "--manifest-path",
+            self.manifest_path
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+        ])?
+        .metadata()?;
+        let Some(current_env) = env_toml::Environment::get(
+            metadata.workspace_root.as_std_path(),
+            &self.env.loam_env(DEFAULT_ENV),
+        )?
+        else {
+            return Err(Error::NoEnvironmentsToml);
+        };
+        let registry = current_env
+            .network
+            .registry
+            .clone()
+            .ok_or(Error::NoRegistry)?;
+
+        let network = clients::Args::add_network_to_env(
+            metadata.workspace_root.as_std_path(),
+            &current_env.network,
+        )?;
+        clients::Args::handle_accounts(current_env.accounts.as_deref(), &network).await?;
+        let owner = match &self.owner {
+            Some(owner) => owner.clone(),
+            None => std::env::var("STELLAR_ACCOUNT").map_err(|_| Error::MissingSourceAccount)?,
+        };
+
+        let PackageVersion { name, version } = &self.package;
+        let mut args = vec![
+            "--id",
+            &registry,
+            "--",
+            "deploy",
+            "--owner",
+            &owner,
+            "--name",
+            &self.name,
+            "--package",
+            name,
+        ];
+        if let Some(version) = version {
+            args.extend(["--version", version]);
+        }
+        let init_args = match self.init.split_first() {
+            Some((init_fn, init_args)) => {
+                let spec = self.published_spec(&registry, &network.rpc_url).await?;
+                let init_args = parse_init_args(&spec, init_fn, init_args, identity_address)?;
+                args.extend(["--init_fn", init_fn]);
+                serde_json::to_string(&init_args)?
+            }
+            None => "[]".to_string(),
+        };
+        args.extend(["--init_args", &init_args]);
+
+        eprintln!("🪞 deploying {} as {:?}", self.package, self.name);
+        let result = cli::contract::invoke::Cmd::parse_arg_vec(&args)?
+            .run_against_rpc_server(None, None)
+            .await?
+            .into_result()
+            .expect("no contract id returned by 'deploy'");
+        let contract_id: String = serde_json::from_str(&result)?;
+        eprintln!("    ↳ contract_id: {contract_id}");
+
+        clients::Args::save_contract_alias(&self.name, &contract_id, &current_env.network)?;
+        eprintln!("✅ deployed {} as {:?}", self.package, self.name);
+        Ok(())
+    }
+
+    /// Spec of the release being deployed, fetched from the network through the registry's
+    /// Package Manager, used to parse the initialization arguments
+    async fn published_spec(&self, registry: &str, rpc_url: &str) -> Result<Spec, Error> {
+        let package_manager = cli::contract::invoke::Cmd::parse_arg_vec(&[
+            "--id",
+            registry,
+            "--is-view",
+            "--",
+            "package_manager",
+        ])?
+        .run_against_rpc_server(None, None)
+        .await?
+        .into_result()
+        .expect("no contract id returned by 'package_manager'");
+        let package_manager: String = serde_json::from_str(&package_manager)?;
+
+        let PackageVersion { name, version } = &self.package;
+        let hash = publish::published_hash(&package_manager, name, version.as_deref())
+            .await?
+            .ok_or_else(|| Error::NotPublished(self.package.to_string()))?;
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(hash, &mut bytes)?;
+
+        let wasm = soroban_rpc::Client::new(rpc_url)?
+            .get_remote_wasm_from_hash(stellar_xdr::curr::Hash(bytes))
+            .await?;
+        Ok(Spec::from_wasm(&wasm)?)
+    }
+}
+
+/// Address of the identity saved as `name`, if there is one
+fn identity_address(name: &str) -> Option<String> {
+    let cmd = cli::keys::address::Cmd {
+        name: name.to_string(),
+        hd_path: Some(0),
+        locator: clients::Args::get_config_locator(),
+    };
+    cmd.public_key().ok().map(|address| address.to_string())
+}
+
+/// Parse `--<name> <value>` arguments for `function` in the order of its inputs, the same way
+/// `contract invoke` does, including looking up addresses of named identities with `address`
+fn parse_init_args(
+    spec: &Spec,
+    function: &str,
+    args: &[String],
+    address: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<ScVal>, Error> {
+    let mut values = Map::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(arg) = arg.strip_prefix("--") else {
+            return Err(Error::MalformedInitArg(arg.clone()));
+        };
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, value.to_string()),
+            None => (
+                arg,
+                args.next()
+                    .ok_or_else(|| Error::MalformedInitArg(format!("--{arg}")))?
+                    .clone(),
+            ),
+        };
+        values.insert(name.to_string(), value);
+    }
+
+    let func = spec.find_function(function)?;
+    let parsed = func
+        .inputs
+        .iter()
+        .map(|input| {
+            let name = input.name.to_utf8_string_lossy();
+            match values.remove(&name) {
+                Some(mut value) => {
+                    if matches!(input.type_, ScSpecTypeDef::Address) {
+                        if let Some(address) = address(&value) {
+                            value = address;
+                        }
+                    }
+                    spec.from_string(&value, &input.type_)
+                        .map_err(|error| Error::ParsingInitArg { arg: name, error })
+                }
+                None if matches!(input.type_, ScSpecTypeDef::Option(_)) => Ok(ScVal::Void),
+                None => Err(Error::MissingInitArg {
+                    function: function.to_string(),
+                    arg: name,
+                }),
+            }
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if let Some(arg) = values.into_keys().next() {
+        return Err(Error::UnknownInitArg {
+            function: function.to_string(),
+            arg,
+        });
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::{
+        ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeOption, ScSpecTypeVec,
+        ScString,
+    };
+
+    const ALICE: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+    /// Spec of `init(admin: Address, count: u32, memo: Option<String>)`
+    fn spec() -> Spec {
+        let input = |name: &str, type_| ScSpecFunctionInputV0 {
+            doc: "".try_into().unwrap(),
+            name: name.try_into().unwrap(),
+            type_,
+        };
+        Spec::new(vec![ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+            doc: "".try_into().unwrap(),
+            name: "init".try_into().unwrap(),
+            inputs: vec![
+                input("admin", ScSpecTypeDef::Address),
+                input("count", ScSpecTypeDef::U32),
+                input(
+                    "memo",
+                    ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                        value_type: Box::new(ScSpecTypeDef::String),
+                    })),
+                ),
+            ]
+            .try_into()
+            .unwrap(),
+            outputs: vec![].try_into().unwrap(),
+        })])
+    }
+
+    fn identities(name: &str) -> Option<String> {
+        (name == "alice").then(|| ALICE.to_string())
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    fn parse(args: &[&str]) -> Vec<ScVal> {
+        parse_init_args(&spec(), "init", &self::args(args), identities).unwrap()
+    }
+
+    fn parse_err(args: &[&str]) -> Error {
+        parse_init_args(&spec(), "init", &self::args(args), identities).unwrap_err()
+    }
+
+    #[test]
+    fn parse_package_version() {
+        let parsed = "hello-world@1.0.0-rc.1".parse::<PackageVersion>().unwrap();
+        assert_eq!(parsed.name, "hello-world");
+        assert_eq!(parsed.version.as_deref(), Some("1.0.0-rc.1"));
+        assert_eq!(parsed.to_string(), "hello-world@1.0.0-rc.1");
+
+        let parsed = "hello-world".parse::<PackageVersion>().unwrap();
+        assert_eq!(parsed.version, None);
+        assert_eq!(parsed.to_string(), "hello-world");
+
+        for invalid in ["", "@1.0.0", "hello-world@"] {
+            assert!(invalid.parse::<PackageVersion>().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn parse_init_args_in_input_order() {
+        let alice = spec().from_string(ALICE, &ScSpecTypeDef::Address).unwrap();
+        assert_eq!(
+            parse(&["--count=3", "--admin", "alice", "--memo", "hi"]),
+            vec![
+                alice.clone(),
+                ScVal::U32(3),
+                ScVal::String(ScString("hi".try_into().unwrap()))
+            ]
+        );
+        // Addresses are passed through when they aren't an identity, and options can be left out
+        assert_eq!(
+            parse(&["--admin", ALICE, "--count", "3"]),
+            vec![alice, ScVal::U32(3), ScVal::Void]
+        );
+    }
+
+    #[test]
+    fn parse_init_args_errors() {
+        assert!(matches!(
+            parse_err(&["admin", "alice"]),
+            Error::MalformedInitArg(arg) if arg == "admin"
+        ));
+        assert!(matches!(
+            parse_err(&["--admin"]),
+            Error::MalformedInitArg(arg) if arg == "--admin"
+        ));
+        assert!(matches!(
+            parse_err(&["--admin", "alice"]),
+            Error::MissingInitArg { arg, .. } if arg == "count"
+        ));
+        assert!(matches!(
+            parse_err(&["--admin", "alice", "--count", "3", "--size", "1"]),
+            Error::UnknownInitArg { arg, .. } if arg == "size"
+        ));
+        assert!(matches!(
+            parse_err(&["--admin", "bob", "--count", "3"]),
+            Error::ParsingInitArg { arg, .. } if arg == "admin"
+        ));
+    }
+
+    /// The registry's `init_args` are a `Vec<Val>`, which `contract invoke` parses from the JSON of
+    /// the `ScVal`s
+    #[test]
+    fn init_args_round_trip_as_vec_of_val() {
+        let args = parse(&["--admin", "alice", "--count", "3", "--memo", "hi"]);
+        let vec_of_val = ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+            element_type: Box::new(ScSpecTypeDef::Val),
+        }));
+        let json = serde_json::to_string(&args).unwrap();
+        assert_eq!(
+            spec().from_string(&json, &vec_of_val).unwrap(),
+            ScVal::Vec(Some(args.try_into().unwrap()))
+        );
+    }
+}
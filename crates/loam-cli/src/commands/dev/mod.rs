@@ -11,8 +11,6 @@ use tokio::time;
 
 use crate::commands::build;
 
-use super::build::clients::LoamEnv;
-
 enum Message {
     FileChanged,
 }
@@ -222,7 +220,7 @@ impl Cmd {
         self.build_cmd
             .build_clients_args
             .env
-            .get_or_insert(LoamEnv::Development);
+            .get_or_insert_with(|| "development".to_string());
         Arc::new(Mutex::new(self.build_cmd.clone()))
     }
 }
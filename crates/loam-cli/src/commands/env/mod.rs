@@ -0,0 +1,25 @@
+use clap::Subcommand;
+
+pub mod validate;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Cmd {
+    /// Check environments.toml for unknown settings, environments and contracts, without building
+    /// or connecting to any network
+    Validate(validate::Cmd),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Validate(#[from] validate::Error),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        match self {
+            Cmd::Validate(validate) => validate.run()?,
+        }
+        Ok(())
+    }
+}
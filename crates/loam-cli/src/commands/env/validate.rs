@@ -0,0 +1,231 @@
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use toml_edit::{ImDocument, TableLike};
+
+use crate::commands::build::{
+    self,
+    env_toml::{self, did_you_mean, Environments},
+};
+use crate::CommandParser;
+
+/// Validate environments.toml
+///
+/// Reports settings that don't exist, contracts that aren't in the workspace and other mistakes
+/// that `loam build` would otherwise only stumble on later, pointing at their line in
+/// environments.toml. Exits with an error if there are any, so it can be used in a pre-commit hook.
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Path to Cargo.toml
+    #[arg(long, default_value = "Cargo.toml")]
+    pub manifest_path: PathBuf,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    EnvironmentsToml(#[from] env_toml::Error),
+    #[error(transparent)]
+    Build(#[from] build::Error),
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(transparent)]
+    Clap(#[from] clap::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("⛔ ️no environments.toml found in {0:?}")]
+    NoEnvironmentsToml(PathBuf),
+    #[error("⛔ ️found {0} problem(s) in environments.toml")]
+    Invalid(usize),
+}
+
+impl Cmd {
+    pub fn run(&self) -> Result<(), Error> {
+        let build = build::Cmd::parse_arg_vec(&[
+            "--manifest-path",
+            self.manifest_path
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+        ])?;
+        let metadata = build.metadata()?;
+        let workspace_root = metadata.workspace_root.as_std_path();
+        let path = workspace_root.join("environments.toml");
+        if !path.exists() {
+            return Err(Error::NoEnvironmentsToml(workspace_root.to_path_buf()));
+        }
+        let source = std::fs::read_to_string(&path)?;
+        env_toml::Environment::check_variables(workspace_root)?;
+        let environments = env_toml::Environment::get_all(workspace_root)?;
+        let package_names = build
+            .list_packages()?
+            .into_iter()
+            .map(|p| p.name.replace('-', "_"))
+            .collect::<Vec<_>>();
+
+        let problems = problems(workspace_root, &environments, &package_names);
+        if problems.is_empty() {
+            eprintln!("✅ environments.toml is valid");
+            return Ok(());
+        }
+        let document = ImDocument::parse(source.as_str()).expect("parsed by env_toml already");
+        for (key_path, message) in &problems {
+            match locate(&document, key_path) {
+                Some((line, column)) => {
+                    eprintln!("⚠️ environments.toml:{line}:{column}: {message}");
+                }
+                None => eprintln!("⚠️ environments.toml: {message}"),
+            }
+        }
+        Err(Error::Invalid(problems.len()))
+    }
+}
+
+/// Mistakes in parsed environments, each with the path of keys it is found at
+fn problems(
+    workspace_root: &Path,
+    environments: &Environments,
+    package_names: &[String],
+) -> Vec<(Vec<String>, String)> {
+    let mut problems = Vec::new();
+    for (env, settings) in environments {
+        let at = |keys: &[&str]| {
+            std::iter::once(env.to_string())
+                .chain(keys.iter().map(ToString::to_string))
+                .collect::<Vec<_>>()
+        };
+        let network = &settings.network;
+        if !network.run_locally
+            && network.name.is_none()
+            && (network.rpc_url.is_none() || network.network_passphrase.is_none())
+        {
+            problems.push((
+                at(&["network"]),
+                "network needs a `name`, both an `rpc-url` and a `network-passphrase`, or \
+                 `run-locally = true`"
+                    .to_string(),
+            ));
+        }
+
+        let defaults = settings
+            .accounts
+            .iter()
+            .flatten()
+            .filter(|account| account.default)
+            .map(|account| account.name.as_str())
+            .collect::<Vec<_>>();
+        if defaults.len() > 1 {
+            problems.push((
+                at(&["accounts"]),
+                format!("only one account can be the default; marked as default: {defaults:?}"),
+            ));
+        }
+
+        if let Some(template) = &settings.clients.template {
+            if !workspace_root.join(template).exists() {
+                problems.push((
+                    at(&["clients", "template"]),
+                    format!("client template {} does not exist", template.display()),
+                ));
+            }
+        }
+
+        for (name, contract) in settings.contracts.iter().flatten() {
+            if let Some(problem) =
+                contract_problem(workspace_root, environments, package_names, name, contract)
+            {
+                problems.push((at(&["contracts", name]), problem));
+            }
+            let contracts = || {
+                package_names
+                    .iter()
+                    .map(String::as_str)
+                    .chain(settings.contracts.iter().flatten().map(|(c, _)| c.as_ref()))
+            };
+            if let Some(salt) = &contract.salt {
+                if salt.len() > 64 || !salt.chars().all(|c| c.is_ascii_hexdigit()) {
+                    problems.push((
+                        at(&["contracts", name, "salt"]),
+                        format!("salt of contract {name:?} must be hex of up to 32 bytes"),
+                    ));
+                }
+            }
+            for dependency in &contract.depends_on {
+                if !contracts().any(|c| c == dependency) {
+                    let suggestion = suggest(dependency, contracts());
+                    problems.push((
+                        at(&["contracts", name, "depends-on"]),
+                        format!(
+                            "contract {name:?} depends on {dependency:?}, which is not a contract \
+                             in this workspace or environment{suggestion}"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// What is wrong with a contract's settings, if anything
+fn contract_problem(
+    workspace_root: &Path,
+    environments: &Environments,
+    package_names: &[String],
+    name: &str,
+    contract: &env_toml::Contract,
+) -> Option<String> {
+    if contract.address.is_none() {
+        if contract.id.is_some() || package_names.iter().any(|package| package == name) {
+            return None;
+        }
+        let suggestion = suggest(name, package_names.iter().map(String::as_str));
+        return Some(format!(
+            "no contract named {name:?} in this workspace{suggestion}"
+        ));
+    }
+    if contract.id.is_some() {
+        return Some(format!(
+            "contract {name:?} sets both an `id` and an `address` to spoon"
+        ));
+    }
+    match (&contract.environment, &contract.snapshot) {
+        (None, None) => Some(format!(
+            "spooned contract {name:?} needs an `environment` or a `snapshot` to spoon from"
+        )),
+        (Some(source), _) if !environments.contains_key(source.as_str()) => {
+            let suggestion = suggest(source, environments.keys().map(AsRef::as_ref));
+            Some(format!(
+                "contract {name:?} is spooned from environment {source:?}, which is not in environments.toml{suggestion}"
+            ))
+        }
+        (_, Some(snapshot)) if !workspace_root.join(snapshot).exists() => Some(format!(
+            "contract {name:?} is spooned from snapshot {}, which does not exist",
+            snapshot.display()
+        )),
+        _ => None,
+    }
+}
+
+/// "; did you mean ...?" for the candidate closest to a misspelled `name`, if any
+fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    did_you_mean(name, candidates)
+        .map(|candidate| format!("; did you mean {candidate:?}?"))
+        .unwrap_or_default()
+}
+
+/// Line and column of the last key of `key_path` in `document`
+fn locate(document: &ImDocument<&str>, key_path: &[String]) -> Option<(usize, usize)> {
+    let mut table: &dyn TableLike = document.as_table();
+    let mut span = None;
+    for (i, key) in key_path.iter().enumerate() {
+        let (key, item) = table.get_key_value(key)?;
+        span = key.span().or(span);
+        if i + 1 < key_path.len() {
+            table = item.as_table_like()?;
+        }
+    }
+    let offset = span?.start;
+    let before = &document.raw()[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = offset - before.rfind('\n').map_or(0, |newline| newline + 1) + 1;
+    Some((line, column))
+}
@@ -0,0 +1,167 @@
+use clap::Parser;
+use soroban_cli::commands as cli;
+use soroban_cli::commands::NetworkRunnable;
+use std::path::{Path, PathBuf};
+
+use crate::commands::build::{
+    self,
+    clients::{self, DEFAULT_ENV},
+    env_toml,
+};
+use crate::commands::publish;
+use crate::CommandParser;
+
+/// Code of `NoSuchName` in the Contract Registry's `Error`
+const REGISTRY_NO_SUCH_NAME: u32 = 4;
+
+/// Install a contract deployed through the Contract Registry
+///
+/// Looks up the contract ID registered under a name with the Contract Registry set as `registry`
+/// in the network settings of the current `LOAM_ENV` in `environments.toml`, saves it as a local
+/// contract alias, and fetches its Wasm to `target/loam/<name>.wasm` (with `-` replaced by `_`), so
+/// it can be used with `import_contract!` and to generate TypeScript bindings. With `--network`,
+/// the registry is the one set by any environment using the network of that name instead.
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Name the contract is registered under
+    pub name: String,
+    /// Name of the network to install from, instead of the one in environments.toml
+    #[arg(long)]
+    pub network: Option<String>,
+    /// Path to Cargo.toml
+    #[arg(long, default_value = "Cargo.toml")]
+    pub manifest_path: PathBuf,
+    #[command(flatten)]
+    pub env: clients::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Clients(#[from] clients::Error),
+    #[error(transparent)]
+    EnvironmentsToml(#[from] env_toml::Error),
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(
+        "⛔ ️no environments.toml found; it sets the network and Contract Registry to install from"
+    )]
+    NoEnvironmentsToml,
+    #[error("⛔ ️no `registry` contract set for the network in environments.toml")]
+    NoRegistry,
+    #[error("⛔ ️no contract registered as {0:?}")]
+    NotRegistered(String),
+    #[error(transparent)]
+    ConfigLocator(#[from] cli::config::locator::Error),
+    #[error(transparent)]
+    ContractInvoke(#[from] cli::contract::invoke::Error),
+    #[error(transparent)]
+    ContractFetch(#[from] cli::contract::fetch::Error),
+    #[error(transparent)]
+    Clap(#[from] clap::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let metadata = build::Cmd::parse_arg_vec(&[
+            "--manifest-path",
+            self.manifest_path
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+        ])?
+        .metadata()?;
+        let Some(mut current_env) = env_toml::Environment::get(
+            metadata.workspace_root.as_std_path(),
+            &self.env.loam_env(DEFAULT_ENV),
+        )?
+        else {
+            return Err(Error::NoEnvironmentsToml);
+        };
+        let registry = match &self.network {
+            Some(network) => {
+                current_env.network =
+                    Self::named_network(metadata.workspace_root.as_std_path(), network)?;
+                current_env.network.registry.clone()
+            }
+            None => current_env.network.registry.clone(),
+        }
+        .ok_or(Error::NoRegistry)?;
+
+        let network = clients::Args::add_network_to_env(
+            metadata.workspace_root.as_std_path(),
+            &current_env.network,
+        )?;
+        clients::Args::handle_accounts(current_env.accounts.as_deref(), &network).await?;
+
+        let contract_id = self.contract_id(&registry).await?;
+        eprintln!("🔗 {:?} is {contract_id}", self.name);
+        clients::Args::save_contract_alias(&self.name, &contract_id, &current_env.network)?;
+
+        let wasm_path = metadata
+            .target_directory
+            .as_std_path()
+            .join("loam")
+            .join(format!("{}.wasm", self.name.replace('-', "_")));
+        eprintln!(
+            "📥 fetching {:?} wasm to {}",
+            self.name,
+            wasm_path.display()
+        );
+        let mut fetch = cli::contract::fetch::Cmd::parse_arg_vec(&["--id", &contract_id])?;
+        fetch.out_file = Some(wasm_path);
+        fetch.run().await?;
+        eprintln!("✅ installed {:?}", self.name);
+        Ok(())
+    }
+
+    /// Settings for the network named `name`, with the `registry` that any environment in
+    /// environments.toml sets for it
+    fn named_network(
+        workspace_root: &Path,
+        name: &str,
+    ) -> Result<env_toml::Network, env_toml::Error> {
+        let registry = env_toml::Environment::get_all(workspace_root)?
+            .into_values()
+            .find_map(|env| {
+                (env.network.name.as_deref() == Some(name))
+                    .then_some(env.network.registry)
+                    .flatten()
+            });
+        Ok(env_toml::Network {
+            name: Some(name.to_string()),
+            rpc_url: None,
+            network_passphrase: None,
+            package_manager: None,
+            registry,
+            run_locally: false,
+        })
+    }
+
+    async fn contract_id(&self, registry: &str) -> Result<String, Error> {
+        let result = cli::contract::invoke::Cmd::parse_arg_vec(&[
+            "--id",
+            registry,
+            "--is-view",
+            "--",
+            "fetch_contract_id",
+            "--name",
+            &self.name,
+        ])?
+        .run_against_rpc_server(None, None)
+        .await;
+        match result {
+            Ok(result) => {
+                let contract_id = result
+                    .into_result()
+                    .expect("no contract id returned by 'fetch_contract_id'");
+                Ok(serde_json::from_str(&contract_id)?)
+            }
+            Err(e) if publish::contract_error(&e) == Some(REGISTRY_NO_SUCH_NAME) => {
+                Err(Error::NotRegistered(self.name.clone()))
+            }
+            Err(e) => Err(Error::ContractInvoke(e)),
+        }
+    }
+}
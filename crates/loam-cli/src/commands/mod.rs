@@ -3,8 +3,12 @@ use std::str::FromStr;
 use clap::{command, CommandFactory, FromArgMatches, Parser};
 
 pub mod build;
+pub mod deploy;
 pub mod dev;
+pub mod env;
 pub mod init;
+pub mod install;
+pub mod publish;
 pub mod update_env;
 
 const ABOUT: &str = "Build contracts and generate front ends";
@@ -43,6 +47,10 @@ impl Root {
             Cmd::Build(build_info) => build_info.run().await?,
             Cmd::UpdateEnv(e) => e.run()?,
             Cmd::Dev(dev_info) => dev_info.run().await?,
+            Cmd::Publish(publish) => publish.run().await?,
+            Cmd::Deploy(deploy) => deploy.run().await?,
+            Cmd::Install(install) => install.run().await?,
+            Cmd::Env(env) => env.run()?,
         };
         Ok(())
     }
@@ -69,6 +77,19 @@ pub enum Cmd {
 
     /// Monitor contracts and environments.toml for changes and rebuild as needed
     Dev(dev::Cmd),
+
+    /// Build a contract and publish its Wasm, with a version, to the Package Manager contract configured in `environments.toml`
+    Publish(publish::Cmd),
+
+    /// Deploy a published contract under a unique name through the Contract Registry configured in `environments.toml`, optionally initializing it in the same transaction
+    Deploy(deploy::Cmd),
+
+    /// Save a local alias for a contract registered with the Contract Registry and fetch its Wasm, so it can be imported like a contract built in this workspace
+    Install(install::Cmd),
+
+    /// Work with the environments in `environments.toml`
+    #[command(subcommand)]
+    Env(env::Cmd),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -82,4 +103,12 @@ pub enum Error {
     UpdateEnv(#[from] update_env::Error),
     #[error(transparent)]
     Dev(#[from] dev::Error),
+    #[error(transparent)]
+    Publish(#[from] publish::Error),
+    #[error(transparent)]
+    Deploy(#[from] deploy::Error),
+    #[error(transparent)]
+    Install(#[from] install::Error),
+    #[error(transparent)]
+    Env(#[from] env::Error),
 }
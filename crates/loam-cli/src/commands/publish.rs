@@ -0,0 +1,424 @@
+use clap::Parser;
+use sha2::{Digest, Sha256};
+use soroban_cli::commands as cli;
+use soroban_cli::commands::NetworkRunnable;
+use std::path::PathBuf;
+
+use crate::commands::build::{
+    self,
+    clients::{self, DEFAULT_ENV},
+    env_toml,
+};
+use crate::CommandParser;
+
+/// Publish a contract's Wasm to the Package Manager
+///
+/// Builds the package, installs its Wasm on the network of the current `LOAM_ENV`, and registers
+/// its name, version, Wasm hash and repository with the Package Manager contract set as
+/// `package-manager` in that network's settings in `environments.toml`.
+#[derive(Parser, Debug, Clone)]
+pub struct Cmd {
+    /// Package to publish, which is also the name it is published under
+    #[arg(long)]
+    pub package: String,
+    /// Version to publish
+    ///
+    /// If omitted, the version in the package's Cargo.toml is used.
+    #[arg(long)]
+    pub version: Option<String>,
+    /// Path to Cargo.toml
+    #[arg(long, default_value = "Cargo.toml")]
+    pub manifest_path: PathBuf,
+    #[command(flatten)]
+    pub env: clients::Args,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Build(#[from] build::Error),
+    #[error(transparent)]
+    Clients(#[from] clients::Error),
+    #[error(transparent)]
+    EnvironmentsToml(#[from] env_toml::Error),
+    #[error(transparent)]
+    Metadata(#[from] cargo_metadata::Error),
+    #[error(
+        "⛔ ️no environments.toml found; it sets the network and Package Manager to publish to"
+    )]
+    NoEnvironmentsToml,
+    #[error("⛔ ️no `package-manager` contract set for the network in environments.toml")]
+    NoPackageManager,
+    #[error("⛔ ️{name}@{version} is already published with different Wasm (hash {hash}); publish these changes as a new version")]
+    VersionAlreadyPublished {
+        name: String,
+        version: String,
+        hash: String,
+    },
+    #[error("⛔ ️reading {0:?}: {1}")]
+    ReadingWasm(PathBuf, std::io::Error),
+    #[error("⛔ ️malformed Wasm: {0}")]
+    MalformedWasm(String),
+    #[error(
+        "⛔ ️no account to publish as; set STELLAR_ACCOUNT or make one of the environment's accounts the default"
+    )]
+    MissingSourceAccount,
+    #[error(transparent)]
+    ContractInstall(#[from] cli::contract::install::Error),
+    #[error(transparent)]
+    ContractInvoke(#[from] cli::contract::invoke::Error),
+    #[error(transparent)]
+    Clap(#[from] clap::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Rpc(#[from] soroban_rpc::Error),
+    #[error(transparent)]
+    Xdr(#[from] stellar_xdr::curr::Error),
+    #[error(transparent)]
+    Hex(#[from] hex::FromHexError),
+}
+
+/// Errors the Package Manager contract rejects calls with, mirroring its `Error` enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PackageManagerError {
+    NoSuchPackage = 1,
+    NoSuchVersion = 2,
+    NotOwner = 3,
+    InvalidVersion = 4,
+    VersionNotNewer = 5,
+    InvalidName = 6,
+}
+
+impl TryFrom<u32> for PackageManagerError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, u32> {
+        Ok(match code {
+            1 => Self::NoSuchPackage,
+            2 => Self::NoSuchVersion,
+            3 => Self::NotOwner,
+            4 => Self::InvalidVersion,
+            5 => Self::VersionNotNewer,
+            6 => Self::InvalidName,
+            _ => return Err(code),
+        })
+    }
+}
+
+impl Cmd {
+    pub async fn run(&self) -> Result<(), Error> {
+        let build = build::Cmd::parse_arg_vec(&[
+            "--package",
+            &self.package,
+            "--manifest-path",
+            self.manifest_path
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+        ])?;
+        let metadata = build.metadata()?;
+        let package = metadata
+            .packages
+            .iter()
+            .find(|p| p.name == self.package)
+            .ok_or_else(|| build::Error::PackageNotFound {
+                package: self.package.clone(),
+            })?;
+        let version = self
+            .version
+            .clone()
+            .unwrap_or_else(|| package.version.to_string());
+
+        let Some(current_env) = env_toml::Environment::get(
+            metadata.workspace_root.as_std_path(),
+            &self.env.loam_env(DEFAULT_ENV),
+        )?
+        else {
+            return Err(Error::NoEnvironmentsToml);
+        };
+        let package_manager = current_env
+            .network
+            .package_manager
+            .clone()
+            .ok_or(Error::NoPackageManager)?;
+
+        build.run().await?;
+        let wasm_path = metadata
+            .target_directory
+            .as_std_path()
+            .join("loam")
+            .join(format!("{}.wasm", self.package.replace('-', "_")));
+        let wasm =
+            std::fs::read(&wasm_path).map_err(|e| Error::ReadingWasm(wasm_path.clone(), e))?;
+        let hash = hex::encode(Sha256::digest(&wasm));
+
+        let network = clients::Args::add_network_to_env(
+            metadata.workspace_root.as_std_path(),
+            &current_env.network,
+        )?;
+        clients::Args::handle_accounts(current_env.accounts.as_deref(), &network).await?;
+
+        match published_hash(&package_manager, &self.package, Some(&version)).await? {
+            Some(published) if published == hash => {
+                eprintln!("✅ {}@{version} is already published", self.package);
+                return Ok(());
+            }
+            Some(published) if same_code(&network.rpc_url, &published, &wasm).await? => {
+                eprintln!(
+                    "✅ {}@{version} is already published, built from another commit",
+                    self.package
+                );
+                return Ok(());
+            }
+            Some(_) => {
+                return Err(Error::VersionAlreadyPublished {
+                    name: self.package.clone(),
+                    version,
+                    hash,
+                })
+            }
+            None => {}
+        }
+
+        self.install_and_publish(package, &package_manager, &version, &wasm_path)
+            .await?;
+        eprintln!("✅ published {}@{version}", self.package);
+        Ok(())
+    }
+
+    /// Install the Wasm and register it with the Package Manager as `version`
+    async fn install_and_publish(
+        &self,
+        package: &cargo_metadata::Package,
+        package_manager: &str,
+        version: &str,
+        wasm_path: &std::path::Path,
+    ) -> Result<(), Error> {
+        eprintln!("📲 installing {:?} wasm bytecode on-chain...", self.package);
+        let hash = cli::contract::install::Cmd::parse_arg_vec(&[
+            "--wasm",
+            wasm_path
+                .to_str()
+                .expect("we do not support non-utf8 paths"),
+        ])?
+        .run_against_rpc_server(None, None)
+        .await?
+        .into_result()
+        .expect("no hash returned by 'contract install'")
+        .to_string();
+        eprintln!("    ↳ hash: {hash}");
+
+        eprintln!("📦 publishing {}@{version}", self.package);
+        let author = std::env::var("STELLAR_ACCOUNT").map_err(|_| Error::MissingSourceAccount)?;
+        let repo = package.repository.clone().unwrap_or_default();
+        let package_metadata = serde_json::to_string(
+            &[
+                ("description", &package.description),
+                ("license", &package.license),
+                ("homepage", &package.homepage),
+            ]
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.as_ref()?)))
+            .collect::<std::collections::BTreeMap<_, _>>(),
+        )?;
+        // Quoted as JSON, since `contract invoke` rejects empty values
+        let repo = serde_json::to_string(&repo)?;
+        cli::contract::invoke::Cmd::parse_arg_vec(&[
+            "--id",
+            package_manager,
+            "--",
+            "publish",
+            "--author",
+            &author,
+            "--name",
+            &self.package,
+            "--version",
+            version,
+            "--wasm_hash",
+            &hash,
+            "--repo",
+            &repo,
+            "--metadata",
+            &package_metadata,
+        ])?
+        .run_against_rpc_server(None, None)
+        .await?;
+        Ok(())
+    }
+}
+
+/// The Wasm hash the Package Manager has for `name@version`, if that version has been published
+///
+/// Leaving out the version looks up the latest release.
+pub(crate) async fn published_hash(
+    package_manager: &str,
+    name: &str,
+    version: Option<&str>,
+) -> Result<Option<String>, Error> {
+    let mut args = vec!["--id", package_manager, "--", "fetch_hash", "--name", name];
+    if let Some(version) = version {
+        args.extend(["--version", version]);
+    }
+    let result = cli::contract::invoke::Cmd::parse_arg_vec(&args)?
+        .run_against_rpc_server(None, None)
+        .await;
+    match result {
+        Ok(result) => {
+            let hash = result
+                .into_result()
+                .expect("no hash returned by 'fetch_hash'");
+            Ok(Some(serde_json::from_str(&hash)?))
+        }
+        Err(e) => match contract_error(&e).map(PackageManagerError::try_from) {
+            Some(Ok(PackageManagerError::NoSuchPackage | PackageManagerError::NoSuchVersion)) => {
+                Ok(None)
+            }
+            _ => Err(Error::ContractInvoke(e)),
+        },
+    }
+}
+
+/// Code of the contract error an invocation failed with, if it was rejected by the contract
+///
+/// soroban-cli only passes on the host error of a failed simulation as text, which starts with
+/// the `ScError` the contract returned.
+pub(crate) fn contract_error(e: &cli::contract::invoke::Error) -> Option<u32> {
+    let cli::contract::invoke::Error::Rpc(soroban_rpc::Error::TransactionSimulationFailed(message)) =
+        e
+    else {
+        return None;
+    };
+    let error = message.trim_start_matches("HostError:").trim_start();
+    let code = error.strip_prefix("Error(Contract, #")?;
+    code[..code.find(')')?].parse().ok()
+}
+
+/// Whether the Wasm published with hash `published` only differs from `wasm` in the commit it
+/// was built from
+async fn same_code(rpc_url: &str, published: &str, wasm: &[u8]) -> Result<bool, Error> {
+    let mut hash = [0u8; 32];
+    hex::decode_to_slice(published, &mut hash)?;
+    let published = soroban_rpc::Client::new(rpc_url)?
+        .get_remote_wasm_from_hash(stellar_xdr::curr::Hash(hash))
+        .await?;
+    Ok(code_hash(&published)? == code_hash(wasm)?)
+}
+
+/// Hash of the Wasm leaving out its `pkg_git` meta, which changes with every commit
+fn code_hash(wasm: &[u8]) -> Result<[u8; 32], Error> {
+    use stellar_xdr::curr::{Limited, Limits, ReadXdr, ScMetaEntry, WriteXdr};
+
+    let malformed = || Error::MalformedWasm("section runs past the end of the module".to_string());
+    let mut hasher = Sha256::new();
+    let mut meta = Vec::new();
+    let mut rest = wasm.get(8..).ok_or_else(malformed)?;
+    hasher.update(&wasm[..8]);
+    while let Some((&id, after_id)) = rest.split_first() {
+        let (size, contents) = read_leb128(after_id).ok_or_else(malformed)?;
+        let section = contents.get(..size).ok_or_else(malformed)?;
+        let meta_section = match (id, read_leb128(section)) {
+            (0, Some((len, name))) if name.get(..len) == Some(b"contractmetav0".as_slice()) => {
+                Some(&name[len..])
+            }
+            _ => None,
+        };
+        if let Some(data) = meta_section {
+            let mut read = Limited::new(data, Limits::none());
+            meta.extend(ScMetaEntry::read_xdr_iter(&mut read).collect::<Result<Vec<_>, _>>()?);
+        } else {
+            hasher.update(&rest[..rest.len() - contents.len() + size]);
+        }
+        rest = &contents[size..];
+    }
+    for entry in meta {
+        let ScMetaEntry::ScMetaV0(entry) = &entry;
+        if entry.key.to_string() != "pkg_git" {
+            hasher.update(entry.to_xdr(Limits::none())?);
+        }
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Read an unsigned LEB128 integer, returning it with the bytes that follow it
+fn read_leb128(bytes: &[u8]) -> Option<(usize, &[u8])> {
+    let mut value = 0usize;
+    for (i, byte) in bytes.iter().enumerate().take(5) {
+        value |= usize::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_section(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut contents = vec![u8::try_from(name.len()).unwrap()];
+        contents.extend(name.as_bytes());
+        contents.extend(data);
+        let mut section = vec![0, u8::try_from(contents.len()).unwrap()];
+        section.extend(contents);
+        section
+    }
+
+    fn wasm_with_meta(entries: &[(&str, &str)]) -> Vec<u8> {
+        use stellar_xdr::curr::{Limits, ScMetaEntry, ScMetaV0, WriteXdr};
+
+        let meta = entries
+            .iter()
+            .flat_map(|(key, val)| {
+                ScMetaEntry::ScMetaV0(ScMetaV0 {
+                    key: (*key).try_into().unwrap(),
+                    val: (*val).try_into().unwrap(),
+                })
+                .to_xdr(Limits::none())
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend(custom_section("contractmetav0", &meta));
+        wasm.extend(custom_section("contractspecv0", b"spec"));
+        wasm
+    }
+
+    #[test]
+    fn code_hash_ignores_pkg_git() {
+        let built = wasm_with_meta(&[("pkg_name", "core"), ("pkg_git", "abc")]);
+        let rebuilt = wasm_with_meta(&[("pkg_name", "core"), ("pkg_git", "def-dirty")]);
+        let renamed = wasm_with_meta(&[("pkg_name", "other"), ("pkg_git", "abc")]);
+        assert_ne!(Sha256::digest(&built), Sha256::digest(&rebuilt));
+        assert_eq!(code_hash(&built).unwrap(), code_hash(&rebuilt).unwrap());
+        assert_ne!(code_hash(&built).unwrap(), code_hash(&renamed).unwrap());
+    }
+
+    #[test]
+    fn code_hash_rejects_truncated_wasm() {
+        let wasm = wasm_with_meta(&[("pkg_git", "abc")]);
+        assert!(matches!(
+            code_hash(&wasm[..wasm.len() - 1]),
+            Err(Error::MalformedWasm(_))
+        ));
+    }
+
+    #[test]
+    fn contract_error_reads_the_host_error() {
+        let failed = |message: &str| {
+            cli::contract::invoke::Error::Rpc(soroban_rpc::Error::TransactionSimulationFailed(
+                message.to_string(),
+            ))
+        };
+        assert_eq!(
+            contract_error(&failed(
+                "HostError: Error(Contract, #2)\n\nEvent log (newest first):\n   0: [Diagnostic Event] Error(Contract, #1)"
+            )),
+            Some(2)
+        );
+        assert_eq!(
+            contract_error(&failed("HostError: Error(Budget, ExceededLimit)")),
+            None
+        );
+    }
+}
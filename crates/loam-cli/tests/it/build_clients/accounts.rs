@@ -47,3 +47,42 @@ soroban_token_contract.client = false
         assert!(stderr.contains("Account already exists"));
     });
 }
+
+#[test]
+fn check_accounts_on_mainnet() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(r#"
+[production]
+network = { run-locally = true, rpc-url = "http://localhost:8011/rpc", network-passphrase = "Public Global Stellar Network ; September 2015" }
+
+accounts = [
+    { name = "alice", secret-env = "ALICE_SECRET" },
+    { name = "bob" },
+]
+[production.contracts]
+hello_world.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+"#);
+
+        let stderr = env.loam("build").assert().failure().stderr_as_str();
+        assert!(stderr.contains("imports its keys from $ALICE_SECRET, which is not set"));
+
+        // imported keys are used, but the account is never created on mainnet
+        let stderr = env
+            .loam("build")
+            .env(
+                "ALICE_SECRET",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            )
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("importing keys for \"alice\" from $ALICE_SECRET"));
+        assert!(stderr.contains("account \"alice\" does not exist on mainnet"));
+        assert!(env.cwd.join(".soroban/identity/alice.toml").exists());
+        assert!(!stderr.contains("creating keys"));
+    });
+}
@@ -201,3 +201,224 @@ soroban_token_contract.client = false
             .contains("⛔ ️Contract update not allowed in production for \"hello_world\""));
     });
 }
+
+#[test]
+fn check_contracts_with_id_instead_of_deploying() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        let environments_toml = |increment: &str| {
+            format!(
+                r#"
+development.accounts = [
+    {{ name = "alice" }},
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8013/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract = {increment}
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+"#
+            )
+        };
+        env.set_environments_toml(environments_toml("{ client = true }"));
+        env.loam_env("development", false).assert().success();
+
+        // the alias saved by the first build is checked against a changed local build
+        env.set_environments_toml(environments_toml(
+            r#"{ client = true, id = "soroban_increment_contract" }"#,
+        ));
+        let stderr = env
+            .loam_env("development", true)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("🔎 checking \"soroban_increment_contract\" contract at C"));
+        assert!(stderr.contains("differs from the local build"));
+        assert!(!stderr.contains("installing \"soroban_increment_contract\""));
+        assert!(stderr.contains("🎭 binding \"soroban_increment_contract\" contract"));
+
+        env.set_environments_toml(environments_toml(
+            r#"{ client = true, id = "CDHZEDHOF34QPQYV5ISJYJ54ZVJD4H3LUW2D3TJ5ELJMTIBWAOGIS4BM" }"#,
+        ));
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains(
+            "contract \"soroban_increment_contract\" not found at CDHZEDHOF34QPQYV5ISJYJ54ZVJD4H3LUW2D3TJ5ELJMTIBWAOGIS4BM"
+        ));
+    });
+}
+
+#[test]
+fn extend_ttl_of_deployed_contracts() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8019/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = true
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+"#,
+        );
+        env.loam_env("development", false).assert().success();
+
+        // A freshly deployed contract lives for less than the default threshold
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("✅ Contract \"soroban_increment_contract\" is up to date"));
+        assert!(stderr.contains("⏳ extended \"soroban_increment_contract\" instance TTL"));
+        assert!(stderr.contains("⏳ extended \"soroban_increment_contract\" wasm TTL"));
+
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(!stderr.contains("⏳ extended"));
+    });
+}
+
+#[test]
+fn deploy_contracts_after_their_dependencies() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8016/rpc"
+
+[development.contracts]
+hello_world = { client = true, depends-on = ["soroban_token_contract"] }
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = true
+
+[development.contracts.soroban_token_contract]
+client = true
+depends-on = ["soroban_auth_contract"]
+init = """
+initialize --symbol ABND --decimal 7 --name abundance --admin alice
+mint --amount 2000000 --to ${contracts.soroban_auth_contract.id}
+"""
+"#,
+        );
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        let instantiated = |name: &str| stderr.find(&format!("instantiating \"{name}\"")).unwrap();
+        assert!(instantiated("soroban_auth_contract") < instantiated("soroban_token_contract"));
+        assert!(instantiated("soroban_token_contract") < instantiated("hello_world"));
+        assert!(stderr.contains(
+            "✅ Initialization script for \"soroban_token_contract\" completed successfully"
+        ));
+
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8016/rpc"
+
+[development.contracts]
+soroban_token_contract = { client = true, depends-on = ["soroban_auth_contract"] }
+soroban_auth_contract = { client = true, depends-on = ["soroban_token_contract"] }
+"#,
+        );
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("contracts depend on each other in a cycle"));
+    });
+}
+
+#[test]
+fn deploy_with_salt_and_source_account() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice", default = true },
+    { name = "bob" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8017/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+client = true
+salt = "5a17"
+source-account = "bob"
+fee = 1000
+"#,
+        );
+        let contract_id = |stderr: &str| {
+            stderr
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("↳ contract_id: "))
+                .unwrap()
+                .to_string()
+        };
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        let first = contract_id(&stderr);
+
+        // A fresh network gets the contract at the same ID
+        std::fs::remove_file(env.cwd.join("target/loam/sandbox.json")).unwrap();
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert_eq!(contract_id(&stderr), first);
+
+        // An update can't take the salted ID again, so it is deployed without the salt
+        let stderr = env
+            .loam_env("development", true)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("🔄 Updating contract \"soroban_token_contract\""));
+        assert_ne!(contract_id(&stderr), first);
+    });
+}
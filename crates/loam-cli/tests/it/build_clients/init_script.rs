@@ -1,4 +1,4 @@
-use crate::util::TestEnv;
+use crate::util::{AssertExt, TestEnv};
 
 #[test]
 fn build_command_runs_init() {
@@ -87,3 +87,118 @@ STELLAR_ACCOUNT=bob mint --amount 2000000 --to bob
         ));
     })
 }
+
+#[test]
+fn run_init_script_in_custom_environment() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[preview]
+run-init = true
+allow-http = true
+accounts = [
+    { name = "alice" },
+]
+
+[preview.network]
+run-locally = true
+rpc-url = "http://localhost:8014/rpc"
+
+[preview.contracts]
+hello_world.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[preview.contracts.soroban_token_contract]
+client = true
+init = """
+initialize --symbol ABND --decimal 7 --name abundance --admin alice
+"""
+"#,
+        );
+        let stderr = env
+            .loam_env("preview", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains(
+            "✅ Initialization script for \"soroban_token_contract\" completed successfully"
+        ));
+        let client =
+            std::fs::read_to_string(env.cwd.join("src/contracts/soroban_token_contract.ts"))
+                .unwrap();
+        assert!(client.contains("allowHttp: true"));
+
+        let stderr = env
+            .loam_env("previwe", false)
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("no settings for current LOAM_ENV (\"previwe\") found in environments.toml; did you mean \"preview\"?"));
+    });
+}
+
+#[test]
+fn run_each_init_step_once() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        let environments_toml = |init: &str| {
+            format!(
+                r#"
+development.accounts = [
+    {{ name = "alice" }},
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8015/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+
+[development.contracts.soroban_token_contract]
+client = true
+init = '''{init}'''
+"#
+            )
+        };
+        let init = r#"
+# quoted arguments keep their spaces
+initialize --symbol ABND --decimal 7 --name "abundance token" --admin alice
+mint --amount 2000000 \
+    --to $soroban_token_contract
+"#;
+        env.set_environments_toml(environments_toml(init));
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("--name 'abundance token' --admin alice"));
+        assert!(stderr.contains(" -- mint --amount 2000000 --to C"));
+        assert!(stderr.contains(
+            "✅ Initialization script for \"soroban_token_contract\" completed successfully"
+        ));
+
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(!stderr.contains("Running initialization script"));
+
+        env.set_environments_toml(environments_toml(&format!(
+            "{init}STELLAR_ACCOUNT=alice mint --amount 5 --to alice\n"
+        )));
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(!stderr.contains(" -- initialize"));
+        assert!(stderr.contains("--source-account alice -- mint --amount 5 --to alice"));
+    });
+}
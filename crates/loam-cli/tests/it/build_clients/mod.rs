@@ -5,3 +5,5 @@ mod init_script;
 mod manifest_path;
 mod network;
 mod no_environments;
+mod spoon;
+mod templates;
@@ -63,3 +63,156 @@ soroban_token_contract.client = false
         assert!(stderr.contains("🌐 using lol network\n"));
     });
 }
+
+#[test]
+fn run_network_locally() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8010/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = true
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+"#,
+        );
+
+        let stderr = env
+            .loam_env("development", true)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("🏝️ running local network at http://localhost:8010/rpc"));
+        assert!(stderr.contains("🪞 instantiating \"soroban_increment_contract\" smart contract"));
+        assert!(env.cwd.join("target/loam/sandbox.json").exists());
+
+        // the ledger is kept between runs
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("✅ Contract \"soroban_increment_contract\" is up to date"));
+    });
+}
+
+#[test]
+fn merge_networks_of_every_environment() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8012/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = true
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+
+[production.network]
+rpc-url = "https://soroban-testnet.stellar.org"
+network-passphrase = "Test SDF Network ; September 2015"
+
+[staging.network]
+rpc-url = "https://soroban-testnet.stellar.org"
+network-passphrase = "Some Other Network"
+"#,
+        );
+        let testnet_id = "CDHZEDHOF34QPQYV5ISJYJ54ZVJD4H3LUW2D3TJ5ELJMTIBWAOGIS4BM";
+        std::fs::create_dir_all(env.cwd.join(".soroban/contract-ids")).unwrap();
+        std::fs::write(
+            env.cwd
+                .join(".soroban/contract-ids/soroban_increment_contract.json"),
+            format!(r#"{{"ids":{{"Test SDF Network ; September 2015":"{testnet_id}"}}}}"#),
+        )
+        .unwrap();
+
+        env.loam_env("development", true).assert().success();
+        let index = std::fs::read_to_string(
+            env.cwd
+                .join("packages/soroban_increment_contract/src/index.ts"),
+        )
+        .unwrap();
+        assert!(index.contains("\"development\": {"));
+        assert!(index.contains("\"networkPassphrase\": \"Standalone Network ; February 2017\""));
+        assert!(index.contains("\"production\": {"));
+        assert!(index.contains(&format!("\"contractId\": \"{testnet_id}\"")));
+        assert!(!index.contains("\"staging\""));
+        assert!(index.contains("} as const"));
+    });
+}
+
+#[test]
+fn leave_out_environments_sharing_a_network() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8023/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = true
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+
+[production.network]
+rpc-url = "https://mainnet.example.org"
+network-passphrase = "Public Global Stellar Network ; September 2015"
+
+[staging.network]
+rpc-url = "https://mainnet.example.org"
+network-passphrase = "Public Global Stellar Network ; September 2015"
+"#,
+        );
+        let mainnet_id = "CDHZEDHOF34QPQYV5ISJYJ54ZVJD4H3LUW2D3TJ5ELJMTIBWAOGIS4BM";
+        std::fs::create_dir_all(env.cwd.join(".soroban/contract-ids")).unwrap();
+        std::fs::write(
+            env.cwd
+                .join(".soroban/contract-ids/soroban_increment_contract.json"),
+            format!(
+                r#"{{"ids":{{"Public Global Stellar Network ; September 2015":"{mainnet_id}"}}}}"#
+            ),
+        )
+        .unwrap();
+
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("Leaving \"production\" out"));
+        assert!(stderr.contains("Leaving \"staging\" out"));
+        let index = std::fs::read_to_string(
+            env.cwd
+                .join("packages/soroban_increment_contract/src/index.ts"),
+        )
+        .unwrap();
+        assert!(index.contains("\"development\": {"));
+        assert!(!index.contains("\"production\""));
+        assert!(!index.contains("\"staging\""));
+        assert!(!index.contains(mainnet_id));
+    });
+}
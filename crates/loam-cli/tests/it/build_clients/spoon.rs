@@ -0,0 +1,86 @@
+use crate::util::{AssertExt, TestEnv};
+
+#[test]
+fn spoon_from_snapshot() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8020/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+
+[development.contracts.soroban_increment_contract]
+client = true
+init = "increment"
+"#,
+        );
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        let contract_id = |stderr: &str| {
+            stderr
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("↳ contract_id: "))
+                .unwrap()
+                .to_string()
+        };
+        let increment_id = contract_id(&stderr);
+
+        // Spoon the incremented counter from the sandbox's ledger into a fresh one
+        let sandbox = env.cwd.join("target/loam/sandbox.json");
+        std::fs::rename(&sandbox, env.cwd.join("snapshot.json")).unwrap();
+        env.set_environments_toml(format!(
+            r#"
+development.accounts = [
+    {{ name = "alice" }},
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8020/rpc"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = false
+
+[development.contracts.counter]
+snapshot = "snapshot.json"
+address = "{increment_id}"
+"#
+        ));
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("🥄 spooning \"counter\" from snapshot.json"));
+        assert!(stderr.contains("🥄 writing 1 storage entries for \"counter\""));
+        let counter_id = contract_id(&stderr);
+        assert_ne!(counter_id, increment_id);
+        let ledger = std::fs::read_to_string(&sandbox).unwrap();
+        assert!(ledger.contains(&format!("\"contract\": \"{counter_id}\"")));
+        assert!(ledger.contains("\"symbol\": \"COUNTER\""));
+
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("✅ Contract \"counter\" is up to date"));
+    });
+}
@@ -0,0 +1,127 @@
+use crate::util::{AssertExt, TestEnv};
+
+#[test]
+fn write_clients_from_template() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8018/rpc"
+
+[development.clients]
+template = "templates/contract.js.tmpl"
+out-dir = "src/lib/contracts"
+index = true
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = false
+soroban_token_contract.client = true
+"#,
+        );
+        std::fs::create_dir_all(env.cwd.join("templates")).unwrap();
+        std::fs::write(
+            env.cwd.join("templates/contract.js.tmpl"),
+            r"import { Client } from '{{ name }}';
+// {{env}}
+export default new Client({
+  contractId: '{{contract_id}}',
+  networkPassphrase: '{{network_passphrase}}',
+  rpcUrl: '{{rpc_url}}',
+  allowHttp: {{allow_http}},
+});
+",
+        )
+        .unwrap();
+        env.loam_env("development", false).assert().success();
+
+        let client =
+            std::fs::read_to_string(env.cwd.join("src/lib/contracts/soroban_token_contract.js"))
+                .unwrap();
+        assert!(client.contains("import { Client } from 'soroban_token_contract';"));
+        assert!(client.contains("// development"));
+        assert!(client.contains("  contractId: 'C"));
+        assert!(client.contains("networkPassphrase: 'Standalone Network ; February 2017'"));
+        assert!(client.contains("rpcUrl: 'http://localhost:8018/rpc'"));
+        assert!(client.contains("allowHttp: true"));
+        let index = std::fs::read_to_string(env.cwd.join("src/lib/contracts/index.js")).unwrap();
+        assert_eq!(
+            index,
+            "export { default as soroban_token_contract } from './soroban_token_contract';\n"
+        );
+
+        // Anything else in double braces is left as it is, such as JSX's inline styles
+        std::fs::write(
+            env.cwd.join("templates/contract.js.tmpl"),
+            "export const id = '{{contract_address}}';\nexport const Id = () => <b style={{ color: 'red' }}>{{contract_id}}</b>;\n",
+        )
+        .unwrap();
+        env.loam_env("development", true).assert().success();
+        let client =
+            std::fs::read_to_string(env.cwd.join("src/lib/contracts/soroban_token_contract.js"))
+                .unwrap();
+        assert!(client.contains("export const id = '{{contract_address}}';"));
+        assert!(client.contains("<b style={{ color: 'red' }}>C"));
+    });
+}
+
+#[test]
+fn write_dependency_ids_into_template() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+development.accounts = [
+    { name = "alice" },
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8021/rpc"
+
+[development.clients]
+template = "templates/contract.js.tmpl"
+
+[development.contracts]
+hello_world.client = false
+soroban_increment_contract.client = false
+soroban_custom_types_contract.client = false
+soroban_auth_contract.client = true
+soroban_token_contract = { client = true, depends-on = ["soroban_auth_contract"] }
+"#,
+        );
+        std::fs::create_dir_all(env.cwd.join("templates")).unwrap();
+        std::fs::write(
+            env.cwd.join("templates/contract.js.tmpl"),
+            "export const id = '{{contract_id}}';\nexport const auth = '{{ contracts.soroban_auth_contract.id }}';\n",
+        )
+        .unwrap();
+        let stderr = env
+            .loam_env("development", false)
+            .assert()
+            .success()
+            .stderr_as_str();
+        let auth_id = stderr
+            .split("instantiating \"soroban_auth_contract\"")
+            .nth(1)
+            .and_then(|rest| {
+                rest.lines()
+                    .find_map(|line| line.trim().strip_prefix("↳ contract_id: "))
+            })
+            .unwrap();
+
+        let client =
+            std::fs::read_to_string(env.cwd.join("src/contracts/soroban_token_contract.js"))
+                .unwrap();
+        assert!(
+            client.contains(&format!("export const auth = '{auth_id}';")),
+            "{client}"
+        );
+    });
+}
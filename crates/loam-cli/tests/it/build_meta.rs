@@ -0,0 +1,110 @@
+use assert_cmd::Command;
+use assert_fs::TempDir;
+use soroban_env_host::xdr::{ScMetaEntry, ScMetaV0};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// A clean checkout of the workspace's last commit, so whether its tree is dirty is up to the test
+fn checkout() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    git(
+        &workspace_root(),
+        &[
+            "clone",
+            "--quiet",
+            "--shared",
+            ".",
+            dir.path().to_str().unwrap(),
+        ],
+    );
+    // The lock file isn't committed; copy it so the checkout builds with the same dependencies
+    std::fs::copy(
+        workspace_root().join("Cargo.lock"),
+        dir.path().join("Cargo.lock"),
+    )
+    .unwrap();
+    dir
+}
+
+/// Build `example-core` in `workspace_root` and read the meta embedded in its Wasm
+fn build_meta(workspace_root: &Path, envs: &[(&str, &Path)]) -> BTreeMap<String, String> {
+    let out_dir = TempDir::new().unwrap();
+    Command::cargo_bin("loam")
+        .unwrap()
+        .current_dir(workspace_root)
+        .env("CARGO_TARGET_DIR", self::workspace_root().join("target"))
+        .envs(envs.iter().copied())
+        .args(["build", "--package", "example-core", "--out-dir"])
+        .arg(out_dir.path())
+        .assert()
+        .success();
+
+    let wasm = std::fs::read(out_dir.path().join("example_core.wasm")).unwrap();
+    soroban_spec_tools::contract::Spec::new(&wasm)
+        .unwrap()
+        .meta
+        .into_iter()
+        .map(|ScMetaEntry::ScMetaV0(ScMetaV0 { key, val })| (key.to_string(), val.to_string()))
+        .collect()
+}
+
+#[test]
+fn embed_package_and_git_meta() {
+    let checkout = checkout();
+    let meta = build_meta(checkout.path(), &[]);
+    assert_eq!(meta["pkg_name"], "example-core");
+    assert_eq!(meta["pkg_version"], "0.0.0");
+    let core_manifest: toml::Value = toml::from_str(
+        &std::fs::read_to_string(
+            checkout
+                .path()
+                .join("crates/loam-subcontract-core/Cargo.toml"),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        meta["subcontract_loam_subcontract_core"],
+        core_manifest["package"]["version"].as_str().unwrap()
+    );
+
+    let head = git(checkout.path(), &["rev-parse", "HEAD"]);
+    assert_eq!(meta.get("pkg_git"), Some(&head));
+}
+
+#[test]
+fn mark_dirty_trees_in_git_meta() {
+    let checkout = checkout();
+    let readme = checkout.path().join("README.md");
+    let mut contents = std::fs::read_to_string(&readme).unwrap();
+    contents.push_str("\nUncommitted change\n");
+    std::fs::write(&readme, contents).unwrap();
+
+    let meta = build_meta(checkout.path(), &[]);
+    let head = git(checkout.path(), &["rev-parse", "HEAD"]);
+    assert_eq!(meta.get("pkg_git"), Some(&format!("{head}-dirty")));
+}
+
+#[test]
+fn leave_out_git_meta_outside_git() {
+    let checkout = checkout();
+    // Point git at a directory that isn't a repository, as if the workspace weren't checked out
+    let not_a_repo = TempDir::new().unwrap();
+    let meta = build_meta(checkout.path(), &[("GIT_DIR", not_a_repo.path())]);
+    assert_eq!(meta["pkg_name"], "example-core");
+    assert_eq!(meta.get("pkg_git"), None);
+}
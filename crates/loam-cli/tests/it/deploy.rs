@@ -0,0 +1,133 @@
+use fs_extra::dir::{copy, CopyOptions};
+use std::path::PathBuf;
+
+use crate::util::{AssertExt, TestEnv};
+
+/// Replace the fixture's contracts with this repository's Package Manager and Contract Registry
+fn use_registry_contracts(env: &TestEnv) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .canonicalize()
+        .unwrap();
+    let contracts = env.cwd.join("contracts");
+    std::fs::remove_dir_all(&contracts).unwrap();
+    std::fs::create_dir_all(&contracts).unwrap();
+    for contract in ["names", "package-manager", "registry"] {
+        copy(
+            root.join("contracts").join(contract),
+            &contracts,
+            &CopyOptions::new(),
+        )
+        .unwrap();
+    }
+    std::fs::copy(root.join("Cargo.lock"), env.cwd.join("Cargo.lock")).unwrap();
+    let crates = root.join("crates");
+    std::fs::write(
+        env.cwd.join("Cargo.toml"),
+        format!(
+            r#"[workspace]
+resolver = "2"
+members = ["contracts/*"]
+
+[workspace.dependencies]
+loam-sdk = {{ path = "{sdk}" }}
+loam-subcontract-core = {{ path = "{core}" }}
+loam-names = {{ path = "./contracts/names" }}
+
+[profile.release]
+opt-level = "z"
+overflow-checks = true
+debug = 0
+strip = "symbols"
+panic = "abort"
+codegen-units = 1
+lto = true
+"#,
+            sdk = crates.join("loam-sdk").display(),
+            core = crates.join("loam-subcontract-core").display(),
+        ),
+    )
+    .unwrap();
+}
+
+/// ID of the contract `loam build` saved as `alias` on the local network
+fn contract_id(env: &TestEnv, alias: &str) -> String {
+    let saved =
+        std::fs::read_to_string(env.cwd.join(format!(".soroban/contract-ids/{alias}.json")))
+            .unwrap();
+    let saved: serde_json::Value = serde_json::from_str(&saved).unwrap();
+    saved["ids"]["Standalone Network ; February 2017"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn deploy_through_registry() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        use_registry_contracts(env);
+        let environments_toml = |package_manager: &str, registry: &str| {
+            format!(
+                r#"
+development.accounts = [
+    {{ name = "alice", default = true }},
+]
+
+[development.network]
+run-locally = true
+rpc-url = "http://localhost:8024/rpc"
+package-manager = "{package_manager}"
+registry = "{registry}"
+
+[development.contracts]
+loam_package_manager = {{ client = true, init = "admin_set --new_admin alice" }}
+
+[development.contracts.loam_registry]
+client = true
+depends-on = ["loam_package_manager"]
+init = """
+admin_set --new_admin alice
+set_package_manager --package_manager ${{contracts.loam_package_manager.id}}
+"""
+"#
+            )
+        };
+        env.set_environments_toml(environments_toml("", ""));
+        env.loam_env("development", false).assert().success();
+        env.set_environments_toml(environments_toml(
+            &contract_id(env, "loam_package_manager"),
+            &contract_id(env, "loam_registry"),
+        ));
+
+        env.loam("publish")
+            .env("LOAM_ENV", "development")
+            .args(["--package", "loam-package-manager"])
+            .assert()
+            .success();
+
+        let stderr = env
+            .loam("deploy")
+            .env("LOAM_ENV", "development")
+            .args([
+                "loam-package-manager@0.1.0",
+                "--name",
+                "packages",
+                "--",
+                "admin_set",
+                "--new_admin",
+                "alice",
+            ])
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("✅ deployed loam-package-manager@0.1.0 as \"packages\""));
+        assert!(env.cwd.join(".soroban/contract-ids/packages.json").exists());
+
+        // The name is taken now, so deploying under it again fails
+        env.loam("deploy")
+            .env("LOAM_ENV", "development")
+            .args(["loam-package-manager", "--name", "packages"])
+            .assert()
+            .failure();
+    });
+}
@@ -0,0 +1,134 @@
+use crate::util::{AssertExt, TestEnv};
+
+#[test]
+fn report_problems_with_their_line() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[development]
+network = { run-locally = true }
+accounts = [
+    { name = "alice" },
+]
+
+[development.contracts]
+soroban_incremnt_contract.client = true
+hello_world = { client = true, depends-on = ["soroban_token_contrct"] }
+
+[developmnt.network]
+name = "testnet"
+"#,
+        );
+        let stderr = env
+            .loam("env")
+            .arg("validate")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains(
+            "environments.toml:9:1: no contract named \"soroban_incremnt_contract\" in this workspace; did you mean \"soroban_increment_contract\"?"
+        ));
+        assert!(stderr.contains(
+            "environments.toml:10:32: contract \"hello_world\" depends on \"soroban_token_contrct\", which is not a contract in this workspace or environment; did you mean \"soroban_token_contract\"?"
+        ));
+        assert!(stderr.contains("found 2 problem(s) in environments.toml"));
+
+        env.set_environments_toml(
+            r#"
+[production.network]
+name = "testnet"
+run-localy = true
+"#,
+        );
+        let stderr = env
+            .loam("env")
+            .arg("validate")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("TOML parse error at line 4, column 1"));
+        assert!(stderr.contains("unknown field `run-localy`"));
+
+        env.set_environments_toml(
+            r#"
+[production.network]
+name = "testnet"
+
+[production.contracts]
+hello_world.client = true
+"#,
+        );
+        let stderr = env
+            .loam("env")
+            .arg("validate")
+            .assert()
+            .success()
+            .stderr_as_str();
+        assert!(stderr.contains("✅ environments.toml is valid"));
+    });
+}
+
+#[test]
+fn extend_and_interpolate_environments() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(
+            r#"
+[production]
+network = { rpc-url = "${RPC_URL}", network-passphrase = "Standalone Network ; February 2017" }
+accounts = [
+    { name = "alice" },
+]
+
+[production.contracts]
+hello_world.client = true
+
+[development]
+extends = "production"
+
+[development.contracts]
+soroban_incremnt_contract.client = true
+"#,
+        );
+        let stderr = env
+            .loam("env")
+            .arg("validate")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("${RPC_URL} in environment \"development\" is not set"));
+
+        // variables come from .env too, and development inherits production's contracts
+        std::fs::write(
+            env.cwd.join(".env"),
+            "RPC_URL=\"http://localhost:8000/rpc\"\n",
+        )
+        .unwrap();
+        let stderr = env
+            .loam("env")
+            .arg("validate")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("no contract named \"soroban_incremnt_contract\""));
+        assert!(stderr.contains("found 1 problem(s) in environments.toml"));
+
+        env.set_environments_toml(
+            r#"
+[production]
+extends = "development"
+
+[development]
+extends = "production"
+"#,
+        );
+        let stderr = env
+            .loam("env")
+            .arg("validate")
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains(
+            "environments extend each other in a cycle: development → production → development"
+        ));
+    });
+}
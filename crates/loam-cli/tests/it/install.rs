@@ -0,0 +1,52 @@
+use crate::util::{AssertExt, TestEnv};
+
+const ENVIRONMENTS_TOML: &str = r#"
+[production.network]
+run-locally = true
+rpc-url = "http://localhost:8011/rpc"
+
+[staging.network]
+name = "lol"
+registry = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+"#;
+
+#[test]
+fn install_from_named_network() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(ENVIRONMENTS_TOML);
+        // a network named "lol" that nothing is listening on
+        std::fs::create_dir_all(env.cwd.join(".soroban/network")).unwrap();
+        env.modify_file(
+            ".soroban/network/lol.toml",
+            r#"
+rpc_url = "http://localhost:8022/rpc"
+network_passphrase = "Standalone Network ; February 2017"
+"#,
+        );
+
+        let stderr = env
+            .loam("install")
+            .args(["hello", "--network", "lol"])
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("🌐 using lol network"), "{stderr}");
+        assert!(!stderr.contains("running local network"), "{stderr}");
+    });
+}
+
+#[test]
+fn install_from_named_network_without_registry() {
+    TestEnv::from("soroban-init-boilerplate", |env| {
+        env.set_environments_toml(ENVIRONMENTS_TOML);
+
+        let stderr = env
+            .loam("install")
+            .args(["hello", "--network", "testnet"])
+            .assert()
+            .failure()
+            .stderr_as_str();
+        assert!(stderr.contains("no `registry` contract set"), "{stderr}");
+        assert!(!stderr.contains("running local network"), "{stderr}");
+    });
+}
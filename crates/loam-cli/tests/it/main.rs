@@ -1,2 +1,6 @@
 mod build_clients;
+mod build_meta;
+mod deploy;
+mod env_validate;
+mod install;
 mod util;
@@ -1,3 +1,6 @@
+use std::collections::BTreeMap;
+
+use cargo_metadata::Package;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
@@ -31,3 +34,47 @@ pub fn generate(contract: &Ident, methods: &[&TokenStream]) -> TokenStream {
 pub fn generate_boilerplate(name: &syn::Ident, methods: &[&TokenStream]) -> TokenStream {
     generate(name, methods)
 }
+
+/// Embed the contract's package name, version and repository, the version of each subcontract
+/// crate it depends on, and the git commit passed by `loam build` as `contractmeta` entries
+pub fn generate_meta(subcontracts: &[Package]) -> TokenStream {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    // Dependencies can show up more than once in the tree, but each key can only be set once
+    let mut entries = BTreeMap::from([
+        ("pkg_name".to_string(), var("CARGO_PKG_NAME")),
+        ("pkg_version".to_string(), var("CARGO_PKG_VERSION")),
+        ("pkg_repo".to_string(), var("CARGO_PKG_REPOSITORY")),
+        ("pkg_git".to_string(), var("LOAM_GIT_HASH")),
+    ]);
+    entries.extend(subcontracts.iter().map(|p| {
+        (
+            format!("subcontract_{}", p.name.replace('-', "_")),
+            p.version.to_string(),
+        )
+    }));
+    let entries = entries
+        .iter()
+        .filter(|(_, val)| !val.is_empty())
+        .map(|(key, val)| {
+            quote! {
+                loam_sdk::soroban_sdk::contractmeta!(key = #key, val = #val);
+            }
+        });
+    quote! {
+        #(#entries)*
+        // Tracked by cargo, so a new commit from `loam build` rebuilds the contract with it
+        const _: Option<&str> = option_env!("LOAM_GIT_HASH");
+    }
+}
+
+/// Override `Core::version` to report the version of the contract crate
+pub fn generate_version() -> TokenStream {
+    quote! {
+        fn version() -> loam_sdk::soroban_sdk::String {
+            loam_sdk::soroban_sdk::String::from_str(
+                loam_sdk::soroban_sdk::env(),
+                env!("CARGO_PKG_VERSION"),
+            )
+        }
+    }
+}
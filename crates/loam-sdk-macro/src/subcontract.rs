@@ -1,3 +1,4 @@
+use cargo_metadata::Package;
 use itertools::Itertools;
 use proc_macro2::{Group, Ident, TokenStream, TokenTree};
 use quote::quote;
@@ -184,7 +185,8 @@ pub fn derive_contract_impl(args: TokenStream, trait_impls: Item) -> TokenStream
     let mut impls = TokenStream::new();
     let idents: Vec<(Ident, Ident)> = parse_idents(args);
 
-    let all_traits = find_deps();
+    let subcontracts = subcontract_deps();
+    let all_traits = find_traits(&subcontracts);
 
     let methods = idents
         .iter()
@@ -193,24 +195,33 @@ pub fn derive_contract_impl(args: TokenStream, trait_impls: Item) -> TokenStream
         .collect::<Vec<_>>();
 
     for (first, second) in idents {
+        // The contract's version is only known here, so `Core` reports it instead of its `Impl`
+        let version = (first == "Core").then(contract::generate_version);
         impls.extend(quote! {
             impl #first for Contract {
                 type Impl = #second;
+                #version
             }
         });
     }
     let outer_impl = contract::generate_boilerplate(&strukt.ident, &methods);
+    let meta = contract::generate_meta(&subcontracts);
     quote! {
         #outer_impl
         #impls
+        #meta
     }
 }
 
-fn find_deps() -> Traits {
-    let cargo_file = crate::manifest();
-    loam_build::deps::subcontract(&cargo_file)
+fn subcontract_deps() -> Vec<Package> {
+    loam_build::deps::subcontract(&crate::manifest())
         .map_err(|e| panic!("{e:#?}"))
         .expect("failed to find all contract deps")
+}
+
+fn find_traits(subcontracts: &[Package]) -> Traits {
+    let cargo_file = crate::manifest();
+    subcontracts
         .iter()
         .map(|i| i.manifest_path.as_std_path())
         .chain([cargo_file.as_path()])
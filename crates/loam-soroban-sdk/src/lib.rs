@@ -2,6 +2,7 @@
 
 pub use soroban_sdk::*;
 pub mod into_key;
+pub mod ttl;
 
 pub use into_key::IntoKey;
 
@@ -12,12 +13,30 @@ pub trait Lazy: Sized {
     fn set_lazy(self);
 }
 
+#[cfg(target_family = "wasm")]
 static mut ENV: Option<Env> = None;
 
+#[cfg(not(target_family = "wasm"))]
+extern crate std;
+
+// Tests run in parallel threads, each with its own environment. References to it are handed out
+// as `'static`, so every environment set is leaked rather than dropped when replaced.
+#[cfg(not(target_family = "wasm"))]
+std::thread_local! {
+    static ENV: core::cell::Cell<Option<&'static Env>> = const { core::cell::Cell::new(None) };
+}
+
+#[cfg(target_family = "wasm")]
 pub fn set_env(env: Env) {
     unsafe { ENV = Some(env) };
 }
 
+#[cfg(not(target_family = "wasm"))]
+pub fn set_env(env: Env) {
+    let env: &'static Env = std::boxed::Box::leak(std::boxed::Box::new(env));
+    ENV.with(|current| current.set(Some(env)));
+}
+
 /// Returns a reference to the current environment.
 ///
 /// # Panics
@@ -26,10 +45,22 @@ pub fn set_env(env: Env) {
 /// It is expected that the environment is always initialized before this
 /// function is called in normal operation.
 #[must_use]
+#[cfg(target_family = "wasm")]
 pub fn env() -> &'static Env {
     unsafe { ENV.as_ref().unwrap() }
 }
 
+/// Returns a reference to the current thread's environment.
+///
+/// # Panics
+///
+/// This function will panic if the environment has not been initialized.
+#[must_use]
+#[cfg(not(target_family = "wasm"))]
+pub fn env() -> &'static Env {
+    ENV.with(core::cell::Cell::get).unwrap()
+}
+
 impl<T> Lazy for T
 where
     T: IntoKey + TryFromVal<Env, Val> + IntoVal<Env, Val>,
@@ -0,0 +1,23 @@
+//! How long contract storage is kept alive.
+//!
+//! Entries are extended as they are used, so storage that is in use never has to be restored. An
+//! entry is only extended once its TTL drops below a threshold, a day short of the amount it is
+//! extended to, so most calls do not pay for an extension.
+
+use crate::env;
+
+/// Ledgers closed in a day, at 5 seconds per ledger
+pub const DAY_IN_LEDGERS: u32 = 17280;
+
+/// Ledgers the contract instance is extended to live for
+pub const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+/// Extend the contract instance once it has fewer ledgers than this left to live
+pub const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Extend the TTL of the current contract's instance and Wasm
+pub fn extend_instance() {
+    env()
+        .storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
@@ -1,5 +1,5 @@
 use loam_sdk::{
-    soroban_sdk::{self, contracttype, env, symbol_short, Address, BytesN, Lazy, Symbol},
+    soroban_sdk::{self, contracttype, env, symbol_short, Address, BytesN, Lazy, String, Symbol},
     subcontract,
 };
 
@@ -49,6 +49,10 @@ impl IsCore for Admin {
         self.admin_get().unwrap().require_auth();
         env().deployer().update_current_contract_wasm(wasm_hash);
     }
+
+    fn version(&self) -> String {
+        String::from_str(env(), env!("CARGO_PKG_VERSION"))
+    }
 }
 
 #[subcontract]
@@ -62,4 +66,7 @@ pub trait IsCore {
 
     /// Admin can redeploy the contract with given hash.
     fn redeploy(&self, wasm_hash: loam_sdk::soroban_sdk::BytesN<32>);
+
+    /// Version of the contract's crate, so redeploys can be audited on-chain.
+    fn version(&self) -> loam_sdk::soroban_sdk::String;
 }
@@ -0,0 +1,33 @@
+use loam_sdk::soroban_sdk::{self, contracterror};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum FungibleError {
+    /// The token has not been initialized with `ft_init`
+    NotInitialized = 1,
+    /// The token has already been initialized
+    AlreadyInitialized = 2,
+    /// The contract's Core admin must be set before initializing the token
+    NoCoreAdmin = 3,
+    /// Tokens support at most 18 decimals
+    TooManyDecimals = 4,
+    /// Amounts cannot be negative
+    NegativeAmount = 5,
+    /// The balance is lower than the amount being spent
+    InsufficientBalance = 6,
+    /// The allowance is lower than the amount being spent, or has expired
+    InsufficientAllowance = 7,
+    /// The expiration ledger of a non-zero allowance is in the past
+    InvalidExpirationLedger = 8,
+    /// The address has been deauthorized by the admin
+    Deauthorized = 9,
+    /// The operation results in an integer overflow
+    Overflow = 10,
+    /// Minting would take the total supply past the cap
+    CapExceeded = 11,
+    /// The cap is negative, below the total supply, or higher than the current cap
+    InvalidCap = 12,
+    /// The address is not allowed to mint tokens
+    NotMinter = 13,
+}
@@ -0,0 +1,57 @@
+//! Events published by a fungible token, with the topics and data defined by SEP-41 and the
+//! admin interface of the Stellar Asset Contract.
+use loam_sdk::soroban_sdk::{self, env, symbol_short, Address, Symbol};
+
+/// `from` allowed `spender` to spend `amount` of its tokens until `expiration_ledger`
+pub fn approve(from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+    let topics = (symbol_short!("approve"), from, spender);
+    env().events().publish(topics, (amount, expiration_ledger));
+}
+
+/// `amount` tokens were moved from `from` to `to`
+pub fn transfer(from: Address, to: Address, amount: i128) {
+    let topics = (symbol_short!("transfer"), from, to);
+    env().events().publish(topics, amount);
+}
+
+/// `amount` tokens were burned from `from`
+pub fn burn(from: Address, amount: i128) {
+    let topics = (symbol_short!("burn"), from);
+    env().events().publish(topics, amount);
+}
+
+/// `admin` minted `amount` tokens to `to`
+pub fn mint(admin: Address, to: Address, amount: i128) {
+    let topics = (symbol_short!("mint"), admin, to);
+    env().events().publish(topics, amount);
+}
+
+/// `admin` clawed back `amount` tokens from `from`
+pub fn clawback(admin: Address, from: Address, amount: i128) {
+    let topics = (symbol_short!("clawback"), admin, from);
+    env().events().publish(topics, amount);
+}
+
+/// `admin` handed the token over to `new_admin`
+pub fn set_admin(admin: Address, new_admin: Address) {
+    let topics = (symbol_short!("set_admin"), admin);
+    env().events().publish(topics, new_admin);
+}
+
+/// `admin` changed whether `id` is authorized to hold and transfer tokens
+pub fn set_authorized(admin: Address, id: Address, authorize: bool) {
+    let topics = (Symbol::new(env(), "set_authorized"), admin, id);
+    env().events().publish(topics, authorize);
+}
+
+/// `admin` set the maximum total supply to `cap`
+pub fn set_cap(admin: Address, cap: i128) {
+    let topics = (symbol_short!("set_cap"), admin);
+    env().events().publish(topics, cap);
+}
+
+/// `admin` allowed or disallowed `id` to mint tokens
+pub fn set_minter(admin: Address, id: Address, allowed: bool) {
+    let topics = (Symbol::new(env(), "set_minter"), admin, id);
+    env().events().publish(topics, allowed);
+}
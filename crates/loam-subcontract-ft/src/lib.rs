@@ -1,41 +1,43 @@
 #![no_std]
 use loam_sdk::{soroban_sdk::Lazy, subcontract};
+
+mod error;
+pub mod events;
+mod storage;
+mod token;
+
+pub use error::FungibleError;
+pub use storage::{AllowanceValue, Metadata};
+pub use token::FungibleToken;
+
 /// The `IsFungible` trait defines methods for implementing a fungible token on the Soroban blockchain.
 /// Fungible tokens are assets that can be exchanged for one another, like a standard currency.
+///
+/// It follows the token interface from [SEP-41](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0041.md),
+/// so a loam token can be used by wallets, the Stellar Asset Contract client and other contracts
+/// that expect the standard interface. Implementations should publish the events in [`events`].
 #[subcontract]
 pub trait IsFungible {
-    /// Determines the amount of tokens that one address is allowed to spend on behalf of another address.
+    /// Returns the amount of tokens that `spender` is allowed to spend on behalf of `from`.
     fn allowance(
         &self,
         from: loam_sdk::soroban_sdk::Address,
         spender: loam_sdk::soroban_sdk::Address,
     ) -> i128;
 
-    /// Increases the allowance that one address can spend on behalf of another address.
-    fn increase_allowance(
-        &mut self,
-        from: loam_sdk::soroban_sdk::Address,
-        spender: loam_sdk::soroban_sdk::Address,
-        amount: i128,
-    );
-
-    /// Decreases the allowance that one address can spend on behalf of another address.
-    fn decrease_allowance(
+    /// Sets the amount of tokens that `spender` can spend on behalf of `from`, until `expiration_ledger`.
+    /// Overrides any existing allowance.
+    fn approve(
         &mut self,
         from: loam_sdk::soroban_sdk::Address,
         spender: loam_sdk::soroban_sdk::Address,
         amount: i128,
+        expiration_ledger: u32,
     );
 
     /// Returns the balance of tokens held by a specific address.
     fn balance(&self, id: loam_sdk::soroban_sdk::Address) -> i128;
 
-    /// Returns the spendable balance of tokens for a specific address.
-    fn spendable_balance(&self, id: loam_sdk::soroban_sdk::Address) -> i128;
-
-    /// Checks if a specific address is authorized.
-    fn authorized(&self, id: loam_sdk::soroban_sdk::Address) -> bool;
-
     /// Transfers tokens from one address to another.
     fn transfer(
         &mut self,
@@ -64,26 +66,38 @@ pub trait IsFungible {
         amount: i128,
     );
 
-    /// Sets the authorization status of a specific address.
-    fn set_authorized(&mut self, id: loam_sdk::soroban_sdk::Address, authorize: bool);
+    /// Returns the number of decimal places the token supports.
+    fn decimals(&self) -> u32;
 
-    /// Mints a specified amount of tokens to a specific address.
-    fn mint(&mut self, to: loam_sdk::soroban_sdk::Address, amount: i128);
+    /// Returns the name of the token.
+    fn name(&self) -> loam_sdk::soroban_sdk::String;
 
-    /// Retrieves a specified amount of tokens from a specific address (clawback).
-    fn clawback(&mut self, from: loam_sdk::soroban_sdk::Address, amount: i128);
+    /// Returns the symbol of the token.
+    fn symbol(&self) -> loam_sdk::soroban_sdk::String;
+}
+
+/// The `IsFungibleAdmin` trait defines the privileged methods of a fungible token, matching the admin
+/// interface of the Stellar Asset Contract. They are kept apart from [`IsFungible`] because they are
+/// not part of SEP-41.
+#[subcontract]
+pub trait IsFungibleAdmin {
+    /// Returns the admin of the token.
+    fn admin(&self) -> loam_sdk::soroban_sdk::Address;
 
     /// Sets a new admin address.
     fn set_admin(&mut self, new_admin: loam_sdk::soroban_sdk::Address);
 
-    /// Returns the number of decimal places the token supports.
-    fn decimals(&self) -> u32;
+    /// Checks if a specific address is authorized to hold and transfer tokens.
+    fn authorized(&self, id: loam_sdk::soroban_sdk::Address) -> bool;
+
+    /// Sets the authorization status of a specific address.
+    fn set_authorized(&mut self, id: loam_sdk::soroban_sdk::Address, authorize: bool);
 
-    /// Returns the name of the token as a byte array.
-    fn name(&self) -> loam_sdk::soroban_sdk::Bytes;
+    /// Mints a specified amount of tokens to a specific address.
+    fn mint(&mut self, to: loam_sdk::soroban_sdk::Address, amount: i128);
 
-    /// Returns the symbol of the token as a byte array.
-    fn symbol(&self) -> loam_sdk::soroban_sdk::Bytes;
+    /// Retrieves a specified amount of tokens from a specific address (clawback).
+    fn clawback(&mut self, from: loam_sdk::soroban_sdk::Address, amount: i128);
 }
 
 #[subcontract]
@@ -92,8 +106,46 @@ pub trait IsInitable {
     fn ft_init(
         &mut self,
         admin: loam_sdk::soroban_sdk::Address,
-        name: loam_sdk::soroban_sdk::Bytes,
-        symbol: loam_sdk::soroban_sdk::Bytes,
+        name: loam_sdk::soroban_sdk::String,
+        symbol: loam_sdk::soroban_sdk::String,
         decimals: u32,
     );
 }
+
+/// The `IsSupply` trait exposes the total supply of a token, which [`FungibleToken`] tracks as
+/// tokens are minted, burned and clawed back.
+#[subcontract]
+pub trait IsSupply {
+    /// Returns the total amount of tokens in circulation.
+    fn total_supply(&self) -> i128;
+}
+
+/// The `IsCapped` trait limits the total supply of a token. Minting past the cap fails.
+#[subcontract]
+pub trait IsCapped {
+    /// Returns the maximum total supply, if one has been set.
+    fn cap(&self) -> Option<i128>;
+
+    /// Sets the maximum total supply. Once set, the cap can only be lowered, and never below the
+    /// current total supply.
+    fn set_cap(&mut self, cap: i128);
+}
+
+/// The `IsMinters` trait lets the admin allow other addresses to mint tokens, so a token can have
+/// several minters without sharing the admin key.
+#[subcontract]
+pub trait IsMinters {
+    /// Checks if a specific address is allowed to mint tokens.
+    fn is_minter(&self, id: loam_sdk::soroban_sdk::Address) -> bool;
+
+    /// Allows or disallows a specific address to mint tokens.
+    fn set_minter(&mut self, id: loam_sdk::soroban_sdk::Address, allowed: bool);
+
+    /// Mints a specified amount of tokens to a specific address, on behalf of an allowed minter.
+    fn mint_by(
+        &mut self,
+        minter: loam_sdk::soroban_sdk::Address,
+        to: loam_sdk::soroban_sdk::Address,
+        amount: i128,
+    );
+}
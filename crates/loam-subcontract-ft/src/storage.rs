@@ -0,0 +1,226 @@
+//! Storage used by [`FungibleToken`](crate::FungibleToken).
+//!
+//! Every balance and allowance is its own ledger entry, so a transfer only touches the entries of
+//! the two addresses involved and each entry's TTL is extended as it is used.
+#![allow(clippy::enum_variant_names)]
+pub(crate) use loam_sdk::soroban_sdk::ttl::extend_instance;
+use loam_sdk::soroban_sdk::ttl::DAY_IN_LEDGERS;
+use loam_sdk::soroban_sdk::{self, contracttype, env, panic_with_error, Address, String};
+
+use crate::FungibleError;
+
+pub(crate) const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub(crate) enum StorageKey {
+    FtMetadata,
+    FtAdmin,
+    FtBalance(Address),
+    FtAllowance(Address, Address),
+    FtDeauthorized(Address),
+    FtSupply,
+    FtCap,
+    FtMinter(Address),
+}
+
+#[contracttype(export = false)]
+pub struct Metadata {
+    pub decimals: u32,
+    pub name: String,
+    pub symbol: String,
+}
+
+#[contracttype(export = false)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+pub(crate) fn has_metadata() -> bool {
+    env().storage().instance().has(&StorageKey::FtMetadata)
+}
+
+pub(crate) fn read_metadata() -> Metadata {
+    env()
+        .storage()
+        .instance()
+        .get(&StorageKey::FtMetadata)
+        .unwrap_or_else(|| panic_with_error!(env(), FungibleError::NotInitialized))
+}
+
+pub(crate) fn write_metadata(metadata: &Metadata) {
+    env()
+        .storage()
+        .instance()
+        .set(&StorageKey::FtMetadata, metadata);
+}
+
+pub(crate) fn read_admin() -> Address {
+    env()
+        .storage()
+        .instance()
+        .get(&StorageKey::FtAdmin)
+        .unwrap_or_else(|| panic_with_error!(env(), FungibleError::NotInitialized))
+}
+
+pub(crate) fn write_admin(admin: &Address) {
+    env().storage().instance().set(&StorageKey::FtAdmin, admin);
+}
+
+pub(crate) fn read_balance(id: &Address) -> i128 {
+    let key = StorageKey::FtBalance(id.clone());
+    let storage = env().storage().persistent();
+    if let Some(balance) = storage.get::<_, i128>(&key) {
+        storage.extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        balance
+    } else {
+        0
+    }
+}
+
+fn write_balance(id: &Address, amount: i128) {
+    let key = StorageKey::FtBalance(id.clone());
+    let storage = env().storage().persistent();
+    storage.set(&key, &amount);
+    storage.extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+pub(crate) fn receive_balance(id: &Address, amount: i128) {
+    let balance = read_balance(id)
+        .checked_add(amount)
+        .unwrap_or_else(|| panic_with_error!(env(), FungibleError::Overflow));
+    write_balance(id, balance);
+}
+
+pub(crate) fn spend_balance(id: &Address, amount: i128) {
+    let balance = read_balance(id);
+    if balance < amount {
+        panic_with_error!(env(), FungibleError::InsufficientBalance);
+    }
+    write_balance(id, balance - amount);
+}
+
+/// An expired allowance reads as zero, but keeps its expiration ledger
+pub(crate) fn read_allowance(from: &Address, spender: &Address) -> AllowanceValue {
+    let key = StorageKey::FtAllowance(from.clone(), spender.clone());
+    match env().storage().temporary().get::<_, AllowanceValue>(&key) {
+        Some(allowance) if allowance.expiration_ledger < env().ledger().sequence() => {
+            AllowanceValue {
+                amount: 0,
+                expiration_ledger: allowance.expiration_ledger,
+            }
+        }
+        Some(allowance) => allowance,
+        None => AllowanceValue {
+            amount: 0,
+            expiration_ledger: 0,
+        },
+    }
+}
+
+/// Allowances live in temporary storage until their expiration ledger, after which they can be
+/// dropped from the ledger
+pub(crate) fn write_allowance(
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    let sequence = env().ledger().sequence();
+    if amount > 0 && expiration_ledger < sequence {
+        panic_with_error!(env(), FungibleError::InvalidExpirationLedger);
+    }
+    let key = StorageKey::FtAllowance(from.clone(), spender.clone());
+    let storage = env().storage().temporary();
+    storage.set(
+        &key,
+        &AllowanceValue {
+            amount,
+            expiration_ledger,
+        },
+    );
+    if amount > 0 {
+        let live_for = expiration_ledger - sequence;
+        storage.extend_ttl(&key, live_for, live_for);
+    }
+}
+
+pub(crate) fn spend_allowance(from: &Address, spender: &Address, amount: i128) {
+    let allowance = read_allowance(from, spender);
+    if allowance.amount < amount {
+        panic_with_error!(env(), FungibleError::InsufficientAllowance);
+    }
+    if amount > 0 {
+        write_allowance(
+            from,
+            spender,
+            allowance.amount - amount,
+            allowance.expiration_ledger,
+        );
+    }
+}
+
+/// Addresses are authorized unless the admin deauthorizes them
+pub(crate) fn is_authorized(id: &Address) -> bool {
+    !env()
+        .storage()
+        .persistent()
+        .has(&StorageKey::FtDeauthorized(id.clone()))
+}
+
+pub(crate) fn write_authorized(id: &Address, authorize: bool) {
+    let key = StorageKey::FtDeauthorized(id.clone());
+    let storage = env().storage().persistent();
+    if authorize {
+        storage.remove(&key);
+    } else {
+        storage.set(&key, &());
+        storage.extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+}
+
+pub(crate) fn read_supply() -> i128 {
+    env()
+        .storage()
+        .instance()
+        .get(&StorageKey::FtSupply)
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_supply(supply: i128) {
+    env()
+        .storage()
+        .instance()
+        .set(&StorageKey::FtSupply, &supply);
+}
+
+pub(crate) fn read_cap() -> Option<i128> {
+    env().storage().instance().get(&StorageKey::FtCap)
+}
+
+pub(crate) fn write_cap(cap: i128) {
+    env().storage().instance().set(&StorageKey::FtCap, &cap);
+}
+
+pub(crate) fn is_minter(id: &Address) -> bool {
+    let key = StorageKey::FtMinter(id.clone());
+    let storage = env().storage().persistent();
+    let minter = storage.has(&key);
+    if minter {
+        storage.extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+    minter
+}
+
+pub(crate) fn write_minter(id: &Address, allowed: bool) {
+    let key = StorageKey::FtMinter(id.clone());
+    let storage = env().storage().persistent();
+    if allowed {
+        storage.set(&key, &());
+        storage.extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    } else {
+        storage.remove(&key);
+    }
+}
@@ -0,0 +1,247 @@
+use loam_sdk::soroban_sdk::{env, panic_with_error, Address, Lazy, String};
+use loam_subcontract_core::admin::{Admin, IsCore};
+
+use crate::{
+    events,
+    storage::{self, Metadata},
+    FungibleError, IsCapped, IsFungible, IsFungibleAdmin, IsInitable, IsMinters, IsSupply,
+};
+
+/// Implementation of the fungible token subcontracts, following the Soroban token example.
+///
+/// ```ignore
+/// #[derive_contract(
+///     Core(Admin),
+///     Fungible(FungibleToken),
+///     FungibleAdmin(FungibleToken),
+///     Initable(FungibleToken)
+/// )]
+/// pub struct Contract;
+/// ```
+///
+/// Balances and allowances are each stored in their own ledger entry, every
+/// method checks the auth of the address it spends from, and amounts are checked for overflows.
+/// `ft_init` requires the auth of the contract's Core admin, so it cannot be front-run.
+#[derive(Lazy, Default)]
+pub struct FungibleToken;
+
+fn check_nonnegative_amount(amount: i128) {
+    if amount < 0 {
+        panic_with_error!(env(), FungibleError::NegativeAmount);
+    }
+}
+
+fn check_authorized(id: &Address) {
+    if !storage::is_authorized(id) {
+        panic_with_error!(env(), FungibleError::Deauthorized);
+    }
+}
+
+fn increase_supply(amount: i128) {
+    let supply = storage::read_supply()
+        .checked_add(amount)
+        .unwrap_or_else(|| panic_with_error!(env(), FungibleError::Overflow));
+    if storage::read_cap().is_some_and(|cap| supply > cap) {
+        panic_with_error!(env(), FungibleError::CapExceeded);
+    }
+    storage::write_supply(supply);
+}
+
+fn decrease_supply(amount: i128) {
+    storage::write_supply(storage::read_supply() - amount);
+}
+
+fn mint_to(minter: Address, to: Address, amount: i128) {
+    check_authorized(&to);
+    storage::extend_instance();
+    increase_supply(amount);
+    storage::receive_balance(&to, amount);
+    events::mint(minter, to, amount);
+}
+
+impl IsInitable for FungibleToken {
+    fn ft_init(&mut self, admin: Address, name: String, symbol: String, decimals: u32) {
+        let Some(core_admin) = Admin::get_lazy().and_then(|admin| admin.admin_get()) else {
+            panic_with_error!(env(), FungibleError::NoCoreAdmin);
+        };
+        core_admin.require_auth();
+        if storage::has_metadata() {
+            panic_with_error!(env(), FungibleError::AlreadyInitialized);
+        }
+        if decimals > 18 {
+            panic_with_error!(env(), FungibleError::TooManyDecimals);
+        }
+        storage::write_admin(&admin);
+        storage::write_metadata(&Metadata {
+            decimals,
+            name,
+            symbol,
+        });
+    }
+}
+
+impl IsFungible for FungibleToken {
+    fn allowance(&self, from: Address, spender: Address) -> i128 {
+        storage::extend_instance();
+        storage::read_allowance(&from, &spender).amount
+    }
+
+    fn approve(&mut self, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        storage::extend_instance();
+        storage::write_allowance(&from, &spender, amount, expiration_ledger);
+        events::approve(from, spender, amount, expiration_ledger);
+    }
+
+    fn balance(&self, id: Address) -> i128 {
+        storage::extend_instance();
+        storage::read_balance(&id)
+    }
+
+    fn transfer(&mut self, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        check_authorized(&from);
+        check_authorized(&to);
+        storage::extend_instance();
+        storage::spend_balance(&from, amount);
+        storage::receive_balance(&to, amount);
+        events::transfer(from, to, amount);
+    }
+
+    fn transfer_from(&mut self, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        check_nonnegative_amount(amount);
+        check_authorized(&from);
+        check_authorized(&to);
+        storage::extend_instance();
+        storage::spend_allowance(&from, &spender, amount);
+        storage::spend_balance(&from, amount);
+        storage::receive_balance(&to, amount);
+        events::transfer(from, to, amount);
+    }
+
+    fn burn(&mut self, from: Address, amount: i128) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        check_authorized(&from);
+        storage::extend_instance();
+        storage::spend_balance(&from, amount);
+        decrease_supply(amount);
+        events::burn(from, amount);
+    }
+
+    fn burn_from(&mut self, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        check_nonnegative_amount(amount);
+        check_authorized(&from);
+        storage::extend_instance();
+        storage::spend_allowance(&from, &spender, amount);
+        storage::spend_balance(&from, amount);
+        decrease_supply(amount);
+        events::burn(from, amount);
+    }
+
+    fn decimals(&self) -> u32 {
+        storage::read_metadata().decimals
+    }
+
+    fn name(&self) -> String {
+        storage::read_metadata().name
+    }
+
+    fn symbol(&self) -> String {
+        storage::read_metadata().symbol
+    }
+}
+
+impl IsFungibleAdmin for FungibleToken {
+    fn admin(&self) -> Address {
+        storage::read_admin()
+    }
+
+    fn set_admin(&mut self, new_admin: Address) {
+        let admin = storage::read_admin();
+        admin.require_auth();
+        storage::extend_instance();
+        storage::write_admin(&new_admin);
+        events::set_admin(admin, new_admin);
+    }
+
+    fn authorized(&self, id: Address) -> bool {
+        storage::is_authorized(&id)
+    }
+
+    fn set_authorized(&mut self, id: Address, authorize: bool) {
+        let admin = storage::read_admin();
+        admin.require_auth();
+        storage::extend_instance();
+        storage::write_authorized(&id, authorize);
+        events::set_authorized(admin, id, authorize);
+    }
+
+    fn mint(&mut self, to: Address, amount: i128) {
+        check_nonnegative_amount(amount);
+        let admin = storage::read_admin();
+        admin.require_auth();
+        mint_to(admin, to, amount);
+    }
+
+    fn clawback(&mut self, from: Address, amount: i128) {
+        check_nonnegative_amount(amount);
+        let admin = storage::read_admin();
+        admin.require_auth();
+        storage::extend_instance();
+        storage::spend_balance(&from, amount);
+        decrease_supply(amount);
+        events::clawback(admin, from, amount);
+    }
+}
+
+impl IsSupply for FungibleToken {
+    fn total_supply(&self) -> i128 {
+        storage::read_supply()
+    }
+}
+
+impl IsCapped for FungibleToken {
+    fn cap(&self) -> Option<i128> {
+        storage::read_cap()
+    }
+
+    fn set_cap(&mut self, cap: i128) {
+        let admin = storage::read_admin();
+        admin.require_auth();
+        if cap < storage::read_supply() || storage::read_cap().is_some_and(|current| cap > current)
+        {
+            panic_with_error!(env(), FungibleError::InvalidCap);
+        }
+        storage::extend_instance();
+        storage::write_cap(cap);
+        events::set_cap(admin, cap);
+    }
+}
+
+impl IsMinters for FungibleToken {
+    fn is_minter(&self, id: Address) -> bool {
+        storage::is_minter(&id)
+    }
+
+    fn set_minter(&mut self, id: Address, allowed: bool) {
+        let admin = storage::read_admin();
+        admin.require_auth();
+        storage::extend_instance();
+        storage::write_minter(&id, allowed);
+        events::set_minter(admin, id, allowed);
+    }
+
+    fn mint_by(&mut self, minter: Address, to: Address, amount: i128) {
+        minter.require_auth();
+        check_nonnegative_amount(amount);
+        if !storage::is_minter(&minter) {
+            panic_with_error!(env(), FungibleError::NotMinter);
+        }
+        mint_to(minter, to, amount);
+    }
+}
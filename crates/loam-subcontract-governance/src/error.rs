@@ -0,0 +1,33 @@
+use loam_sdk::soroban_sdk::{self, contracterror};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum GovernorError {
+    /// The governor has not been initialized with `gov_init`
+    NotInitialized = 1,
+    /// The governor has already been initialized
+    AlreadyInitialized = 2,
+    /// The contract's Core admin must be set before initializing the governor
+    NoCoreAdmin = 3,
+    /// The quorum must be positive and the voting period longer than zero ledgers
+    InvalidConfig = 4,
+    /// No proposal with this id exists
+    NotFound = 5,
+    /// The address holds no tokens to vote or propose with
+    NoVotingPower = 6,
+    /// The voting period of the proposal is over
+    VotingClosed = 7,
+    /// The voting period of the proposal is not over yet
+    VotingOpen = 8,
+    /// The address has already voted on the proposal
+    AlreadyVoted = 9,
+    /// The proposal did not reach the quorum, or has more votes against than for
+    NotPassed = 10,
+    /// The proposal is not in the status required by the operation
+    InvalidStatus = 11,
+    /// The timelock of the proposal has not passed yet
+    Timelocked = 12,
+    /// The address has no tokens locked in the proposal, or has already unlocked them
+    NothingLocked = 13,
+}
@@ -0,0 +1,32 @@
+//! Events published by a governor as proposals move through their lifecycle.
+use loam_sdk::soroban_sdk::{self, env, symbol_short, Address};
+
+/// `proposer` created proposal `proposal_id`
+pub fn propose(proposer: Address, proposal_id: u32) {
+    let topics = (symbol_short!("propose"), proposer);
+    env().events().publish(topics, proposal_id);
+}
+
+/// `voter` voted on proposal `proposal_id` with `weight` tokens
+pub fn vote(voter: Address, proposal_id: u32, support: bool, weight: i128) {
+    let topics = (symbol_short!("vote"), voter, proposal_id);
+    env().events().publish(topics, (support, weight));
+}
+
+/// `voter` got back the `amount` of tokens they locked to vote on proposal `proposal_id`
+pub fn unlock(voter: Address, proposal_id: u32, amount: i128) {
+    let topics = (symbol_short!("unlock"), voter, proposal_id);
+    env().events().publish(topics, amount);
+}
+
+/// Proposal `proposal_id` passed and can be executed from ledger `eta`
+pub fn queue(proposal_id: u32, eta: u32) {
+    let topics = (symbol_short!("queue"), proposal_id);
+    env().events().publish(topics, eta);
+}
+
+/// Proposal `proposal_id` was executed
+pub fn execute(proposal_id: u32) {
+    let topics = (symbol_short!("execute"), proposal_id);
+    env().events().publish(topics, ());
+}
@@ -0,0 +1,176 @@
+use loam_sdk::soroban_sdk::{env, panic_with_error, token::TokenClient, Address, Lazy, Val};
+use loam_subcontract_core::admin::{Admin, IsCore};
+
+use crate::{
+    events, storage, Action, GovernorConfig, GovernorError, IsGovernor, Proposal, ProposalStatus,
+};
+
+/// Implementation of the governor subcontract.
+///
+/// ```ignore
+/// #[derive_contract(Core(Admin), Governor(Governance))]
+/// pub struct Contract;
+/// ```
+///
+/// To move a contract from a single admin to token holders, initialize the governor and then set
+/// the Core admin to the contract's own address. From then on `redeploy` and admin changes can
+/// only happen through executed proposals.
+///
+/// Voting moves the voter's whole balance into the contract, where it stays until the voter calls
+/// `unlock` after the voting period. Tokens can therefore only vote once per proposal, even if they
+/// change hands, and tokens locked in one proposal cannot vote on another until they are unlocked.
+/// Each proposal keeps the token it was made with, so locked votes are refunded in that token even
+/// if a `Configure` proposal changes it in the meantime.
+#[derive(Lazy, Default)]
+pub struct Governance;
+
+fn voting_power(token: &Address, id: &Address) -> i128 {
+    let power = TokenClient::new(env(), token).balance(id);
+    if power <= 0 {
+        panic_with_error!(env(), GovernorError::NoVotingPower);
+    }
+    power
+}
+
+fn check_status(proposal: &Proposal, status: ProposalStatus) {
+    if proposal.status != status {
+        panic_with_error!(env(), GovernorError::InvalidStatus);
+    }
+}
+
+fn run(action: Action) {
+    match action {
+        Action::Redeploy(wasm_hash) => env().deployer().update_current_contract_wasm(wasm_hash),
+        Action::SetAdmin(new_admin) => {
+            // The proposal passing is the authorization, so the current admin is not asked for it
+            let mut admin = Admin::default();
+            admin.admin_set(new_admin);
+            admin.set_lazy();
+        }
+        Action::Configure(config) => storage::write_config(&config),
+        Action::Invoke(contract, function, args) => {
+            env().invoke_contract::<Val>(&contract, &function, args);
+        }
+    }
+}
+
+impl IsGovernor for Governance {
+    fn gov_init(&mut self, config: GovernorConfig) {
+        let Some(core_admin) = Admin::get_lazy().and_then(|admin| admin.admin_get()) else {
+            panic_with_error!(env(), GovernorError::NoCoreAdmin);
+        };
+        core_admin.require_auth();
+        if storage::has_config() {
+            panic_with_error!(env(), GovernorError::AlreadyInitialized);
+        }
+        storage::write_config(&config);
+    }
+
+    fn gov_config(&self) -> GovernorConfig {
+        storage::read_config()
+    }
+
+    fn propose(&mut self, proposer: Address, action: Action) -> u32 {
+        proposer.require_auth();
+        let config = storage::read_config();
+        voting_power(&config.token, &proposer);
+        storage::extend_instance();
+        let proposal_id = storage::next_proposal_id();
+        storage::write_proposal(
+            proposal_id,
+            &Proposal {
+                proposer: proposer.clone(),
+                action,
+                token: config.token,
+                vote_end: env().ledger().sequence() + config.voting_period,
+                votes_for: 0,
+                votes_against: 0,
+                status: ProposalStatus::Active,
+                eta: 0,
+            },
+        );
+        events::propose(proposer, proposal_id);
+        proposal_id
+    }
+
+    fn vote(&mut self, voter: Address, proposal_id: u32, support: bool) {
+        voter.require_auth();
+        let mut proposal = storage::read_proposal(proposal_id);
+        check_status(&proposal, ProposalStatus::Active);
+        if env().ledger().sequence() > proposal.vote_end {
+            panic_with_error!(env(), GovernorError::VotingClosed);
+        }
+        if storage::read_locked(proposal_id, &voter).is_some() {
+            panic_with_error!(env(), GovernorError::AlreadyVoted);
+        }
+        let weight = voting_power(&proposal.token, &voter);
+        TokenClient::new(env(), &proposal.token).transfer(
+            &voter,
+            &env().current_contract_address(),
+            &weight,
+        );
+        if support {
+            proposal.votes_for += weight;
+        } else {
+            proposal.votes_against += weight;
+        }
+        storage::extend_instance();
+        storage::write_locked(proposal_id, &voter, weight);
+        storage::write_proposal(proposal_id, &proposal);
+        events::vote(voter, proposal_id, support, weight);
+    }
+
+    fn unlock(&mut self, voter: Address, proposal_id: u32) {
+        let proposal = storage::read_proposal(proposal_id);
+        if env().ledger().sequence() <= proposal.vote_end {
+            panic_with_error!(env(), GovernorError::VotingOpen);
+        }
+        let amount = storage::read_locked(proposal_id, &voter).unwrap_or_default();
+        if amount == 0 {
+            panic_with_error!(env(), GovernorError::NothingLocked);
+        }
+        storage::extend_instance();
+        storage::write_locked(proposal_id, &voter, 0);
+        TokenClient::new(env(), &proposal.token).transfer(
+            &env().current_contract_address(),
+            &voter,
+            &amount,
+        );
+        events::unlock(voter, proposal_id, amount);
+    }
+
+    fn queue(&mut self, proposal_id: u32) {
+        let mut proposal = storage::read_proposal(proposal_id);
+        check_status(&proposal, ProposalStatus::Active);
+        let sequence = env().ledger().sequence();
+        if sequence <= proposal.vote_end {
+            panic_with_error!(env(), GovernorError::VotingOpen);
+        }
+        let config = storage::read_config();
+        if proposal.votes_for < config.quorum || proposal.votes_for <= proposal.votes_against {
+            panic_with_error!(env(), GovernorError::NotPassed);
+        }
+        proposal.status = ProposalStatus::Queued;
+        proposal.eta = sequence + config.timelock;
+        storage::extend_instance();
+        storage::write_proposal(proposal_id, &proposal);
+        events::queue(proposal_id, proposal.eta);
+    }
+
+    fn execute(&mut self, proposal_id: u32) {
+        let mut proposal = storage::read_proposal(proposal_id);
+        check_status(&proposal, ProposalStatus::Queued);
+        if env().ledger().sequence() < proposal.eta {
+            panic_with_error!(env(), GovernorError::Timelocked);
+        }
+        proposal.status = ProposalStatus::Executed;
+        storage::extend_instance();
+        storage::write_proposal(proposal_id, &proposal);
+        run(proposal.action);
+        events::execute(proposal_id);
+    }
+
+    fn proposal(&self, proposal_id: u32) -> Proposal {
+        storage::read_proposal(proposal_id)
+    }
+}
@@ -0,0 +1,54 @@
+#![no_std]
+use loam_sdk::{soroban_sdk::Lazy, subcontract};
+
+// Lets the trait below name its types with the same paths as the contracts that use it
+extern crate self as loam_subcontract_governance;
+
+mod error;
+pub mod events;
+mod governance;
+mod storage;
+mod types;
+
+pub use error::GovernorError;
+pub use governance::Governance;
+pub use types::{Action, GovernorConfig, Proposal, ProposalStatus};
+
+/// The `IsGovernor` trait lets token holders control a contract through proposals.
+///
+/// A proposal is voted on for `voting_period` ledgers, with each vote weighted by the voter's
+/// `balance` in the configured token, which can be any contract exposing the `IsFungible`
+/// (SEP-41) interface. Voting locks that balance in the contract until the voting period is over,
+/// so the same tokens cannot be transferred to vote again. A proposal that reaches the quorum with
+/// more votes for than against can be queued, and executed once its timelock has passed.
+#[subcontract]
+pub trait IsGovernor {
+    /// Sets the token, quorum, voting period and timelock. Requires the auth of the Core admin.
+    fn gov_init(&mut self, config: loam_subcontract_governance::GovernorConfig);
+
+    /// Returns the current configuration.
+    fn gov_config(&self) -> loam_subcontract_governance::GovernorConfig;
+
+    /// Creates a proposal to run `action`, returning its id. The proposer must hold voting power.
+    fn propose(
+        &mut self,
+        proposer: loam_sdk::soroban_sdk::Address,
+        action: loam_subcontract_governance::Action,
+    ) -> u32;
+
+    /// Votes for or against a proposal while it is open, locking the voter's token balance in the
+    /// contract as the vote's weight.
+    fn vote(&mut self, voter: loam_sdk::soroban_sdk::Address, proposal_id: u32, support: bool);
+
+    /// Returns the tokens `voter` locked to vote on a proposal, once its voting period is over.
+    fn unlock(&mut self, voter: loam_sdk::soroban_sdk::Address, proposal_id: u32);
+
+    /// Queues a proposal that passed once its voting period is over, starting its timelock.
+    fn queue(&mut self, proposal_id: u32);
+
+    /// Runs the action of a queued proposal once its timelock has passed.
+    fn execute(&mut self, proposal_id: u32);
+
+    /// Returns a proposal.
+    fn proposal(&self, proposal_id: u32) -> loam_subcontract_governance::Proposal;
+}
@@ -0,0 +1,84 @@
+//! Storage used by [`Governance`](crate::Governance).
+#![allow(clippy::enum_variant_names)]
+pub(crate) use loam_sdk::soroban_sdk::ttl::extend_instance;
+use loam_sdk::soroban_sdk::ttl::DAY_IN_LEDGERS;
+use loam_sdk::soroban_sdk::{self, contracttype, env, panic_with_error, Address};
+
+use crate::{GovernorConfig, GovernorError, Proposal};
+
+pub(crate) const PROPOSAL_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PROPOSAL_LIFETIME_THRESHOLD: u32 = PROPOSAL_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub(crate) enum StorageKey {
+    GovConfig,
+    GovProposalCount,
+    GovProposal(u32),
+    GovVoted(u32, Address),
+}
+
+pub(crate) fn has_config() -> bool {
+    env().storage().instance().has(&StorageKey::GovConfig)
+}
+
+pub(crate) fn read_config() -> GovernorConfig {
+    env()
+        .storage()
+        .instance()
+        .get(&StorageKey::GovConfig)
+        .unwrap_or_else(|| panic_with_error!(env(), GovernorError::NotInitialized))
+}
+
+pub(crate) fn write_config(config: &GovernorConfig) {
+    if config.quorum <= 0 || config.voting_period == 0 {
+        panic_with_error!(env(), GovernorError::InvalidConfig);
+    }
+    env()
+        .storage()
+        .instance()
+        .set(&StorageKey::GovConfig, config);
+}
+
+/// Returns the id for a new proposal
+pub(crate) fn next_proposal_id() -> u32 {
+    let storage = env().storage().instance();
+    let id: u32 = storage
+        .get(&StorageKey::GovProposalCount)
+        .unwrap_or_default();
+    storage.set(&StorageKey::GovProposalCount, &(id + 1));
+    id
+}
+
+pub(crate) fn read_proposal(proposal_id: u32) -> Proposal {
+    let key = StorageKey::GovProposal(proposal_id);
+    let storage = env().storage().persistent();
+    let proposal = storage
+        .get(&key)
+        .unwrap_or_else(|| panic_with_error!(env(), GovernorError::NotFound));
+    storage.extend_ttl(&key, PROPOSAL_LIFETIME_THRESHOLD, PROPOSAL_BUMP_AMOUNT);
+    proposal
+}
+
+pub(crate) fn write_proposal(proposal_id: u32, proposal: &Proposal) {
+    let key = StorageKey::GovProposal(proposal_id);
+    let storage = env().storage().persistent();
+    storage.set(&key, proposal);
+    storage.extend_ttl(&key, PROPOSAL_LIFETIME_THRESHOLD, PROPOSAL_BUMP_AMOUNT);
+}
+
+/// Tokens `voter` locked to vote on a proposal and has not unlocked yet, if they voted on it
+pub(crate) fn read_locked(proposal_id: u32, voter: &Address) -> Option<i128> {
+    env()
+        .storage()
+        .persistent()
+        .get(&StorageKey::GovVoted(proposal_id, voter.clone()))
+}
+
+/// Kept after the tokens are unlocked, so the voter cannot vote on the proposal again
+pub(crate) fn write_locked(proposal_id: u32, voter: &Address, amount: i128) {
+    let key = StorageKey::GovVoted(proposal_id, voter.clone());
+    let storage = env().storage().persistent();
+    storage.set(&key, &amount);
+    storage.extend_ttl(&key, PROPOSAL_LIFETIME_THRESHOLD, PROPOSAL_BUMP_AMOUNT);
+}
@@ -0,0 +1,53 @@
+use loam_sdk::soroban_sdk::{self, contracttype, Address, BytesN, Symbol, Val, Vec};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GovernorConfig {
+    /// Token contract whose balances weigh votes, and which votes are locked in through `transfer`;
+    /// changing it only affects proposals made afterwards
+    pub token: Address,
+    /// Minimum number of votes for a proposal to pass
+    pub quorum: i128,
+    /// Number of ledgers a proposal is open for voting
+    pub voting_period: u32,
+    /// Number of ledgers between queueing and executing a proposal
+    pub timelock: u32,
+}
+
+/// What a proposal does once executed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// Updates the contract's Wasm, like `Core`'s `redeploy`
+    Redeploy(BytesN<32>),
+    /// Hands the contract's Core admin to another address
+    SetAdmin(Address),
+    /// Replaces the governor's configuration
+    Configure(GovernorConfig),
+    /// Calls a function of another contract, with this contract as the invoker
+    Invoke(Address, Symbol, Vec<Val>),
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Active,
+    Queued,
+    Executed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposer: Address,
+    pub action: Action,
+    /// Token votes on this proposal are weighed and locked in, set from the config when proposed
+    pub token: Address,
+    /// Last ledger in which votes are accepted
+    pub vote_end: u32,
+    pub votes_for: i128,
+    pub votes_against: i128,
+    pub status: ProposalStatus,
+    /// First ledger in which a queued proposal can be executed
+    pub eta: u32,
+}
@@ -0,0 +1,17 @@
+use loam_sdk::soroban_sdk::{self, contracterror};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum NonFungibleError {
+    /// No token with this id has been minted, or it has been burned
+    NotFound = 1,
+    /// The address does not own the token
+    NotOwner = 2,
+    /// The spender was not approved for the token
+    NotApproved = 3,
+    /// A token with this id has already been minted
+    AlreadyMinted = 4,
+    /// The contract's Core admin must be set before minting
+    NoCoreAdmin = 5,
+}
@@ -0,0 +1,33 @@
+//! Events published by a non-fungible token. Topics follow the fungible token events, with the
+//! token id in place of an amount.
+use loam_sdk::soroban_sdk::{self, env, symbol_short, Address};
+
+/// `owner` allowed `approved` to transfer `token_id`
+pub fn approve(owner: Address, approved: Address, token_id: u32) {
+    let topics = (symbol_short!("approve"), owner, approved);
+    env().events().publish(topics, token_id);
+}
+
+/// `owner` allowed or disallowed `operator` to transfer all of its tokens
+pub fn approve_all(owner: Address, operator: Address, approved: bool) {
+    let topics = (symbol_short!("appr_all"), owner, operator);
+    env().events().publish(topics, approved);
+}
+
+/// `token_id` was moved from `from` to `to`
+pub fn transfer(from: Address, to: Address, token_id: u32) {
+    let topics = (symbol_short!("transfer"), from, to);
+    env().events().publish(topics, token_id);
+}
+
+/// `admin` minted `token_id` to `to`
+pub fn mint(admin: Address, to: Address, token_id: u32) {
+    let topics = (symbol_short!("mint"), admin, to);
+    env().events().publish(topics, token_id);
+}
+
+/// `token_id` was burned by its owner, `from`
+pub fn burn(from: Address, token_id: u32) {
+    let topics = (symbol_short!("burn"), from);
+    env().events().publish(topics, token_id);
+}
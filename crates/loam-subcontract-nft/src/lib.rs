@@ -0,0 +1,79 @@
+#![no_std]
+use loam_sdk::{soroban_sdk::Lazy, subcontract};
+
+mod error;
+pub mod events;
+mod storage;
+mod token;
+
+pub use error::NonFungibleError;
+pub use token::NonFungibleToken;
+
+/// The `IsNonFungible` trait defines methods for implementing a non-fungible token (NFT) on the Soroban blockchain.
+/// Each token is identified by a `token_id` and has a single owner.
+#[subcontract]
+pub trait IsNonFungible {
+    /// Returns the owner of a token.
+    fn owner_of(&self, token_id: u32) -> loam_sdk::soroban_sdk::Address;
+
+    /// Transfers a token from its owner, `from`, to another address.
+    fn transfer(
+        &mut self,
+        from: loam_sdk::soroban_sdk::Address,
+        to: loam_sdk::soroban_sdk::Address,
+        token_id: u32,
+    );
+
+    /// Transfers a token from its owner, `from`, to another address, with a spender address that
+    /// was approved for the token, or for all of the owner's tokens, controlling the transfer.
+    fn transfer_from(
+        &mut self,
+        spender: loam_sdk::soroban_sdk::Address,
+        from: loam_sdk::soroban_sdk::Address,
+        to: loam_sdk::soroban_sdk::Address,
+        token_id: u32,
+    );
+
+    /// Allows `approved` to transfer a single token on behalf of its owner, until the token changes hands.
+    /// Overrides any existing approval for the token.
+    fn approve(
+        &mut self,
+        owner: loam_sdk::soroban_sdk::Address,
+        approved: loam_sdk::soroban_sdk::Address,
+        token_id: u32,
+    );
+
+    /// Allows or disallows `operator` to transfer all of the tokens of `owner`.
+    fn approve_all(
+        &mut self,
+        owner: loam_sdk::soroban_sdk::Address,
+        operator: loam_sdk::soroban_sdk::Address,
+        approved: bool,
+    );
+
+    /// Returns the URI of a token's metadata.
+    fn token_uri(&self, token_id: u32) -> loam_sdk::soroban_sdk::String;
+
+    /// Returns the number of tokens held by a specific address.
+    fn balance(&self, owner: loam_sdk::soroban_sdk::Address) -> u32;
+}
+
+/// The `IsMintable` trait lets the contract's admin create new tokens. It is kept apart from
+/// [`IsNonFungible`] so that collections with a fixed supply can leave it out.
+#[subcontract]
+pub trait IsMintable {
+    /// Mints a new token with the given metadata URI to a specific address.
+    fn mint(
+        &mut self,
+        to: loam_sdk::soroban_sdk::Address,
+        token_id: u32,
+        uri: loam_sdk::soroban_sdk::String,
+    );
+}
+
+/// The `IsBurnable` trait lets token owners destroy their tokens.
+#[subcontract]
+pub trait IsBurnable {
+    /// Burns a token held by a specific address.
+    fn burn(&mut self, from: loam_sdk::soroban_sdk::Address, token_id: u32);
+}
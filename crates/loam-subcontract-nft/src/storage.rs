@@ -0,0 +1,151 @@
+//! Storage used by [`NonFungibleToken`](crate::NonFungibleToken).
+//!
+//! Every token's owner, URI and approval is its own ledger entry, as is every balance, so a transfer
+//! only touches the entries of the token and the two addresses involved.
+#![allow(clippy::enum_variant_names)]
+pub(crate) use loam_sdk::soroban_sdk::ttl::extend_instance;
+use loam_sdk::soroban_sdk::ttl::DAY_IN_LEDGERS;
+use loam_sdk::soroban_sdk::{self, contracttype, env, panic_with_error, Address, String};
+
+use crate::NonFungibleError;
+
+pub(crate) const TOKEN_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const TOKEN_LIFETIME_THRESHOLD: u32 = TOKEN_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[contracttype(export = false)]
+#[derive(Clone)]
+pub(crate) enum StorageKey {
+    NftOwner(u32),
+    NftUri(u32),
+    NftApproved(u32),
+    NftBalance(Address),
+    NftOperator(Address, Address),
+}
+
+fn extend(key: &StorageKey) {
+    env()
+        .storage()
+        .persistent()
+        .extend_ttl(key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+}
+
+pub(crate) fn exists(token_id: u32) -> bool {
+    env()
+        .storage()
+        .persistent()
+        .has(&StorageKey::NftOwner(token_id))
+}
+
+pub(crate) fn read_owner(token_id: u32) -> Address {
+    let key = StorageKey::NftOwner(token_id);
+    let owner = env()
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| panic_with_error!(env(), NonFungibleError::NotFound));
+    extend(&key);
+    owner
+}
+
+pub(crate) fn write_owner(token_id: u32, owner: &Address) {
+    let key = StorageKey::NftOwner(token_id);
+    env().storage().persistent().set(&key, owner);
+    extend(&key);
+}
+
+pub(crate) fn read_uri(token_id: u32) -> String {
+    let key = StorageKey::NftUri(token_id);
+    let uri = env()
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| panic_with_error!(env(), NonFungibleError::NotFound));
+    extend(&key);
+    uri
+}
+
+pub(crate) fn write_uri(token_id: u32, uri: &String) {
+    let key = StorageKey::NftUri(token_id);
+    env().storage().persistent().set(&key, uri);
+    extend(&key);
+}
+
+/// Removes the owner, URI and approval of a burned token
+pub(crate) fn remove_token(token_id: u32) {
+    let storage = env().storage().persistent();
+    storage.remove(&StorageKey::NftOwner(token_id));
+    storage.remove(&StorageKey::NftUri(token_id));
+    storage.remove(&StorageKey::NftApproved(token_id));
+}
+
+pub(crate) fn read_approved(token_id: u32) -> Option<Address> {
+    let key = StorageKey::NftApproved(token_id);
+    let approved = env().storage().persistent().get(&key);
+    if approved.is_some() {
+        extend(&key);
+    }
+    approved
+}
+
+pub(crate) fn write_approved(token_id: u32, approved: &Address) {
+    let key = StorageKey::NftApproved(token_id);
+    env().storage().persistent().set(&key, approved);
+    extend(&key);
+}
+
+pub(crate) fn remove_approved(token_id: u32) {
+    env()
+        .storage()
+        .persistent()
+        .remove(&StorageKey::NftApproved(token_id));
+}
+
+pub(crate) fn is_operator(owner: &Address, operator: &Address) -> bool {
+    let key = StorageKey::NftOperator(owner.clone(), operator.clone());
+    let approved = env().storage().persistent().has(&key);
+    if approved {
+        extend(&key);
+    }
+    approved
+}
+
+pub(crate) fn write_operator(owner: &Address, operator: &Address, approved: bool) {
+    let key = StorageKey::NftOperator(owner.clone(), operator.clone());
+    let storage = env().storage().persistent();
+    if approved {
+        storage.set(&key, &());
+        extend(&key);
+    } else {
+        storage.remove(&key);
+    }
+}
+
+pub(crate) fn read_balance(owner: &Address) -> u32 {
+    let key = StorageKey::NftBalance(owner.clone());
+    let storage = env().storage().persistent();
+    if let Some(balance) = storage.get::<_, u32>(&key) {
+        extend(&key);
+        balance
+    } else {
+        0
+    }
+}
+
+fn write_balance(owner: &Address, balance: u32) {
+    let key = StorageKey::NftBalance(owner.clone());
+    let storage = env().storage().persistent();
+    if balance == 0 {
+        storage.remove(&key);
+    } else {
+        storage.set(&key, &balance);
+        extend(&key);
+    }
+}
+
+pub(crate) fn increment_balance(owner: &Address) {
+    write_balance(owner, read_balance(owner) + 1);
+}
+
+pub(crate) fn decrement_balance(owner: &Address) {
+    write_balance(owner, read_balance(owner) - 1);
+}
@@ -0,0 +1,111 @@
+use loam_sdk::soroban_sdk::{env, panic_with_error, Address, Lazy, String};
+use loam_subcontract_core::admin::{Admin, IsCore};
+
+use crate::{events, storage, IsBurnable, IsMintable, IsNonFungible, NonFungibleError};
+
+/// Implementation of the non-fungible token subcontracts.
+///
+/// ```ignore
+/// #[derive_contract(
+///     Core(Admin),
+///     NonFungible(NonFungibleToken),
+///     Mintable(NonFungibleToken),
+///     Burnable(NonFungibleToken)
+/// )]
+/// pub struct Contract;
+/// ```
+///
+/// Each token's owner, URI and approval are stored in their own ledger entries. Transfers require
+/// the auth of the owner or of an approved spender, and minting requires the auth of the
+/// contract's Core admin.
+#[derive(Lazy, Default)]
+pub struct NonFungibleToken;
+
+fn check_owner(from: &Address, token_id: u32) {
+    if storage::read_owner(token_id) != *from {
+        panic_with_error!(env(), NonFungibleError::NotOwner);
+    }
+}
+
+fn move_token(from: Address, to: Address, token_id: u32) {
+    storage::extend_instance();
+    storage::remove_approved(token_id);
+    storage::write_owner(token_id, &to);
+    storage::decrement_balance(&from);
+    storage::increment_balance(&to);
+    events::transfer(from, to, token_id);
+}
+
+impl IsNonFungible for NonFungibleToken {
+    fn owner_of(&self, token_id: u32) -> Address {
+        storage::read_owner(token_id)
+    }
+
+    fn transfer(&mut self, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        check_owner(&from, token_id);
+        move_token(from, to, token_id);
+    }
+
+    fn transfer_from(&mut self, spender: Address, from: Address, to: Address, token_id: u32) {
+        spender.require_auth();
+        check_owner(&from, token_id);
+        if storage::read_approved(token_id).as_ref() != Some(&spender)
+            && !storage::is_operator(&from, &spender)
+        {
+            panic_with_error!(env(), NonFungibleError::NotApproved);
+        }
+        move_token(from, to, token_id);
+    }
+
+    fn approve(&mut self, owner: Address, approved: Address, token_id: u32) {
+        owner.require_auth();
+        check_owner(&owner, token_id);
+        storage::extend_instance();
+        storage::write_approved(token_id, &approved);
+        events::approve(owner, approved, token_id);
+    }
+
+    fn approve_all(&mut self, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
+        storage::extend_instance();
+        storage::write_operator(&owner, &operator, approved);
+        events::approve_all(owner, operator, approved);
+    }
+
+    fn token_uri(&self, token_id: u32) -> String {
+        storage::read_uri(token_id)
+    }
+
+    fn balance(&self, owner: Address) -> u32 {
+        storage::read_balance(&owner)
+    }
+}
+
+impl IsMintable for NonFungibleToken {
+    fn mint(&mut self, to: Address, token_id: u32, uri: String) {
+        let Some(admin) = Admin::get_lazy().and_then(|admin| admin.admin_get()) else {
+            panic_with_error!(env(), NonFungibleError::NoCoreAdmin);
+        };
+        admin.require_auth();
+        if storage::exists(token_id) {
+            panic_with_error!(env(), NonFungibleError::AlreadyMinted);
+        }
+        storage::extend_instance();
+        storage::write_owner(token_id, &to);
+        storage::write_uri(token_id, &uri);
+        storage::increment_balance(&to);
+        events::mint(admin, to, token_id);
+    }
+}
+
+impl IsBurnable for NonFungibleToken {
+    fn burn(&mut self, from: Address, token_id: u32) {
+        from.require_auth();
+        check_owner(&from, token_id);
+        storage::extend_instance();
+        storage::remove_token(token_id);
+        storage::decrement_balance(&from);
+        events::burn(from, token_id);
+    }
+}